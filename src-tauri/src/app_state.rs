@@ -0,0 +1,300 @@
+use crate::{
+    events::{ErrorCode, PipelineEvent},
+    i18n::{Msg, t},
+    sound_cues::{Cue, SoundCues},
+    transcribe_icon::{Icon, TranscribeIcon},
+};
+use anyhow::{Result, bail};
+use serde::Serialize;
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Emitter, Manager, Wry, menu::MenuItem};
+
+/// Where the dictation and polishing pipeline is. Only one thing happens at a
+/// time, so a recording can't start while a polish is in flight and vice versa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppState {
+    Idle,
+    Recording,
+    Paused,
+    Transcribing,
+    Polishing,
+    Pasting,
+    /// The last run failed. Behaves like `Idle` otherwise.
+    Error,
+}
+
+impl AppState {
+    fn can_become(self, to: AppState) -> bool {
+        use AppState::*;
+
+        match (self, to) {
+            // Whatever is going on can be cancelled or fail
+            (_, Idle | Error) => true,
+            (Idle | Error, Recording | Transcribing | Polishing | Pasting) => true,
+            (Recording, Paused | Transcribing) => true,
+            (Paused, Recording | Transcribing) => true,
+            // Translating with the polishing backend
+            (Transcribing, Polishing | Pasting) => true,
+            (Polishing, Pasting) => true,
+            _ => false,
+        }
+    }
+
+    /// `offline` when no backend is reachable
+    fn icon(self, offline: bool) -> Icon {
+        match self {
+            Self::Idle if offline => Icon::Offline,
+            Self::Recording | Self::Paused => Icon::Recording,
+            Self::Transcribing => Icon::Transcribing,
+            Self::Polishing => Icon::Cleansing,
+            Self::Error => Icon::Error,
+            Self::Idle | Self::Pasting => Icon::Default,
+        }
+    }
+
+    pub fn is_recording(self) -> bool {
+        matches!(self, Self::Recording | Self::Paused)
+    }
+
+    /// The sound played when going from `self` to `to`
+    fn cue(self, to: AppState) -> Option<Cue> {
+        match (self, to) {
+            (Self::Idle | Self::Error, Self::Recording) => Some(Cue::Start),
+            (Self::Recording | Self::Paused, Self::Transcribing) => Some(Cue::Stop),
+            (Self::Pasting, Self::Idle) => Some(Cue::Success),
+            (_, Self::Error) => Some(Cue::Error),
+            _ => None,
+        }
+    }
+
+    /// Waiting on a backend, which can only be cancelled. Delivering the result
+    /// can't be stopped halfway, so `Pasting` isn't offered a cancel.
+    pub fn is_busy(self) -> bool {
+        matches!(self, Self::Transcribing | Self::Polishing)
+    }
+}
+
+/// How long the current recording has been going, not counting pauses
+#[derive(Default)]
+struct RecordingClock {
+    elapsed: Duration,
+    running_since: Option<Instant>,
+}
+
+impl RecordingClock {
+    fn elapsed(&self) -> Duration {
+        self.elapsed + self.running_since.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+
+    fn update(&mut self, from: AppState, to: AppState) {
+        match to {
+            AppState::Recording if from == AppState::Paused => {
+                self.running_since = Some(Instant::now());
+            }
+            AppState::Recording => {
+                *self = Self {
+                    elapsed: Duration::ZERO,
+                    running_since: Some(Instant::now()),
+                };
+            }
+            AppState::Paused => {
+                self.elapsed = self.elapsed();
+                self.running_since = None;
+            }
+            _ => *self = Self::default(),
+        }
+    }
+}
+
+/// Owns the `AppState`. The tray icon, the tray menu and the `state-changed`
+/// event sent to the window follow every transition.
+pub struct StateMachine {
+    state: Mutex<AppState>,
+    clock: Mutex<RecordingClock>,
+    offline: AtomicBool,
+    toggle_item: MenuItem<Wry>,
+    pause_item: MenuItem<Wry>,
+    cancel_item: MenuItem<Wry>,
+}
+
+impl StateMachine {
+    pub fn new(
+        toggle_item: MenuItem<Wry>,
+        pause_item: MenuItem<Wry>,
+        cancel_item: MenuItem<Wry>,
+    ) -> Self {
+        Self {
+            state: Mutex::new(AppState::Idle),
+            clock: Mutex::default(),
+            offline: AtomicBool::new(false),
+            toggle_item,
+            pause_item,
+            cancel_item,
+        }
+    }
+
+    pub fn current(&self) -> AppState {
+        *self.state.lock().unwrap()
+    }
+
+    /// Moves to `to`, or fails without changing anything if the current state
+    /// doesn't allow it
+    pub fn transition(&self, app_handle: &AppHandle, to: AppState) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let from = *state;
+        if !from.can_become(to) {
+            log::warn!("Ignoring transition from {from:?} to {to:?}");
+            bail!("Can't go from {from:?} to {to:?}");
+        }
+        *state = to;
+        self.clock.lock().unwrap().update(from, to);
+        // The tray is updated on the main thread, which may be waiting for this
+        // lock in a menu event
+        drop(state);
+
+        log::info!("State changed from {from:?} to {to:?}");
+        if let Some(cue) = from.cue(to) {
+            app_handle.state::<SoundCues>().play(cue);
+        }
+        self.apply(app_handle, to);
+
+        Ok(())
+    }
+
+    /// Back to `Idle` once the pipeline is done or was cancelled
+    pub fn finish(&self, app_handle: &AppHandle) {
+        _ = self.transition(app_handle, AppState::Idle);
+    }
+
+    /// Moves to `Error` and tells the window why
+    pub fn fail(
+        &self,
+        app_handle: &AppHandle,
+        code: ErrorCode,
+        message: impl Into<String>,
+    ) {
+        PipelineEvent::error(code, message).emit(app_handle);
+        _ = self.transition(app_handle, AppState::Error);
+    }
+
+    /// Shows the offline icon while idle when no backend is reachable
+    pub fn set_offline(&self, app_handle: &AppHandle, offline: bool) {
+        let was_offline = self.offline.swap(offline, Ordering::Relaxed);
+        let state = self.current();
+        if was_offline != offline && state == AppState::Idle {
+            app_handle
+                .state::<TranscribeIcon>()
+                .change_icon(state.icon(offline));
+        }
+    }
+
+    /// Shows how long the recording has been going next to the tray icon.
+    /// Called every second.
+    pub fn tick(&self, app_handle: &AppHandle) {
+        let state = self.current();
+        if state.is_recording() {
+            self.show_timer(app_handle, state);
+        }
+    }
+
+    fn show_timer(&self, app_handle: &AppHandle, state: AppState) {
+        let secs = self.clock.lock().unwrap().elapsed().as_secs();
+        let timer = format!("{}:{:02}", secs / 60, secs % 60);
+        let timer = if state == AppState::Paused {
+            format!("{timer} {}", t(Msg::Paused))
+        } else {
+            timer
+        };
+        app_handle
+            .state::<TranscribeIcon>()
+            .set_progress(Some(&timer));
+    }
+
+    fn apply(&self, app_handle: &AppHandle, state: AppState) {
+        app_handle
+            .state::<TranscribeIcon>()
+            .change_icon(state.icon(self.offline.load(Ordering::Relaxed)));
+
+        if state.is_recording() {
+            self.show_timer(app_handle, state);
+        } else {
+            app_handle.state::<TranscribeIcon>().set_progress(None);
+        }
+
+        let toggle_text = if state.is_recording() {
+            t(Msg::StopRecording)
+        } else if state.is_busy() {
+            t(Msg::Cancel)
+        } else {
+            t(Msg::StartRecording)
+        };
+        let pause_text = if state == AppState::Paused {
+            t(Msg::ResumeRecording)
+        } else {
+            t(Msg::PauseRecording)
+        };
+        let cancellable = state.is_recording() || state.is_busy();
+        if let Err(e) = self
+            .toggle_item
+            .set_text(toggle_text)
+            .and_then(|_| self.pause_item.set_text(pause_text))
+            .and_then(|_| self.pause_item.set_enabled(state.is_recording()))
+            .and_then(|_| self.cancel_item.set_enabled(cancellable))
+        {
+            log::error!("Failed to update tray menu: {}", e);
+        }
+
+        if let Err(e) = app_handle.emit("state-changed", state) {
+            log::error!("Failed to emit state change: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AppState::*;
+
+    #[test]
+    fn anything_can_be_cancelled_or_fail() {
+        for from in [
+            Idle,
+            Recording,
+            Paused,
+            Transcribing,
+            Polishing,
+            Pasting,
+            Error,
+        ] {
+            assert!(from.can_become(Idle), "{from:?} -> Idle");
+            assert!(from.can_become(Error), "{from:?} -> Error");
+        }
+    }
+
+    #[test]
+    fn follows_the_pipeline() {
+        assert!(Idle.can_become(Recording));
+        assert!(Recording.can_become(Paused));
+        assert!(Paused.can_become(Recording));
+        assert!(Recording.can_become(Transcribing));
+        assert!(Transcribing.can_become(Polishing));
+        assert!(Polishing.can_become(Pasting));
+        assert!(Error.can_become(Polishing));
+    }
+
+    #[test]
+    fn only_does_one_thing_at_a_time() {
+        assert!(!Recording.can_become(Recording));
+        assert!(!Recording.can_become(Polishing));
+        assert!(!Polishing.can_become(Recording));
+        assert!(!Transcribing.can_become(Recording));
+        assert!(!Pasting.can_become(Transcribing));
+        assert!(!Idle.can_become(Paused));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::config::ArchiveConfig;
+use anyhow::{Context, Result};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use std::{
+    cmp::Reverse,
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// Recordings are kept gzipped in `~/.config/whistle/recordings`, named after
+/// the id of their history item
+fn archive_dir() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .context("Could not find home directory")?
+        .join(".config/whistle/recordings"))
+}
+
+fn recording_path(history_id: i64) -> Result<PathBuf> {
+    Ok(archive_dir()?.join(format!("{history_id}.wav.gz")))
+}
+
+pub fn has_recording(history_id: i64) -> bool {
+    recording_path(history_id).is_ok_and(|path| path.exists())
+}
+
+/// Compresses and stores `wav` for the history item, then applies the
+/// retention limits
+pub fn save(history_id: i64, wav: &[u8], config: &ArchiveConfig) -> Result<()> {
+    fs::create_dir_all(archive_dir()?)?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(wav)?;
+    fs::write(recording_path(history_id)?, encoder.finish()?)?;
+
+    enforce_retention(&archive_dir()?, config)
+}
+
+/// The WAV recording behind a history item
+pub fn load(history_id: i64) -> Result<Vec<u8>> {
+    let path = recording_path(history_id)?;
+    let compressed = fs::read(&path)
+        .with_context(|| format!("No recording archived for item {history_id}"))?;
+
+    let mut wav = Vec::new();
+    GzDecoder::new(compressed.as_slice()).read_to_end(&mut wav)?;
+
+    Ok(wav)
+}
+
+pub fn delete(history_id: i64) -> Result<()> {
+    let path = recording_path(history_id)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Keeps the archive of a re-transcription pointing at the same recording,
+/// then applies the retention limits like `save`
+pub fn copy(
+    from_history_id: i64,
+    to_history_id: i64,
+    config: &ArchiveConfig,
+) -> Result<()> {
+    fs::copy(
+        recording_path(from_history_id)?,
+        recording_path(to_history_id)?,
+    )?;
+
+    enforce_retention(&archive_dir()?, config)
+}
+
+/// Applies the retention limits to what's already archived, even with archiving
+/// turned off since, so old recordings don't stay around forever
+pub fn prune(config: &ArchiveConfig) -> Result<()> {
+    let dir = archive_dir()?;
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    enforce_retention(&dir, config)
+}
+
+/// Deletes recordings in `dir` older than `max_age_days`, then the oldest ones
+/// until the archive fits in `max_total_mb`
+fn enforce_retention(dir: &Path, config: &ArchiveConfig) -> Result<()> {
+    let mut recordings = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().ok()?;
+            Some((entry.path(), modified, metadata.len()))
+        })
+        .collect::<Vec<_>>();
+
+    // Newest first, so the oldest ones are popped off the end
+    recordings.sort_by_key(|&(_, modified, _)| Reverse(modified));
+
+    if let Some(max_age_days) = config.max_age_days {
+        let max_age = Duration::from_secs(u64::from(max_age_days) * 24 * 60 * 60);
+        let now = SystemTime::now();
+        recordings.retain(|(path, modified, _)| {
+            let expired = now.duration_since(*modified).is_ok_and(|age| age > max_age);
+            if expired {
+                remove(path);
+            }
+            !expired
+        });
+    }
+
+    if let Some(max_total_mb) = config.max_total_mb {
+        let max_total = max_total_mb * 1_048_576;
+        let mut total = recordings.iter().map(|(_, _, len)| len).sum::<u64>();
+        while total > max_total {
+            let Some((path, _, len)) = recordings.pop() else {
+                break;
+            };
+            remove(&path);
+            total -= len;
+        }
+    }
+
+    Ok(())
+}
+
+fn remove(path: &Path) {
+    match fs::remove_file(path) {
+        Ok(()) => log::info!("Removed archived recording {}", path.display()),
+        Err(e) => log::error!("Failed to remove {}: {}", path.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::TempDir;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Writes a recording of `len` bytes last modified `age` ago
+    fn recording(dir: &TempDir, name: &str, len: usize, age: Duration) {
+        let path = dir.path().join(name);
+        fs::write(&path, vec![0; len]).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() - age)
+            .unwrap();
+    }
+
+    fn remaining(dir: &TempDir) -> Vec<String> {
+        let mut names = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn removes_recordings_past_the_max_age() {
+        let dir = TempDir::new().unwrap();
+        recording(&dir, "1.wav.gz", 10, DAY * 10);
+        recording(&dir, "2.wav.gz", 10, DAY * 3);
+        recording(&dir, "3.wav.gz", 10, Duration::ZERO);
+
+        let config = ArchiveConfig {
+            enabled: true,
+            max_age_days: Some(7),
+            max_total_mb: None,
+        };
+        enforce_retention(dir.path(), &config).unwrap();
+
+        assert_eq!(remaining(&dir), ["2.wav.gz", "3.wav.gz"]);
+    }
+
+    #[test]
+    fn removes_the_oldest_recordings_until_under_the_max_size() {
+        let dir = TempDir::new().unwrap();
+        recording(&dir, "1.wav.gz", 600_000, DAY * 3);
+        recording(&dir, "2.wav.gz", 600_000, DAY * 2);
+        recording(&dir, "3.wav.gz", 600_000, DAY);
+
+        let config = ArchiveConfig {
+            enabled: true,
+            max_age_days: None,
+            max_total_mb: Some(1),
+        };
+        enforce_retention(dir.path(), &config).unwrap();
+
+        assert_eq!(remaining(&dir), ["3.wav.gz"]);
+    }
+
+    #[test]
+    fn keeps_everything_without_limits() {
+        let dir = TempDir::new().unwrap();
+        recording(&dir, "1.wav.gz", 600_000, DAY * 400);
+        recording(&dir, "2.wav.gz", 600_000, Duration::ZERO);
+
+        let config = ArchiveConfig {
+            enabled: true,
+            max_age_days: None,
+            max_total_mb: None,
+        };
+        enforce_retention(dir.path(), &config).unwrap();
+
+        assert_eq!(remaining(&dir), ["1.wav.gz", "2.wav.gz"]);
+    }
+}
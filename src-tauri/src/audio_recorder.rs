@@ -5,7 +5,7 @@ use cpal::{
     Stream,
     traits::{DeviceTrait, HostTrait, StreamTrait},
 };
-use hound::{WavSpec, WavWriter};
+use hound::{WavReader, WavSpec, WavWriter};
 use std::{
     sync::{Arc, Mutex},
     time::{Duration, Instant},
@@ -20,7 +20,7 @@ pub struct AudioRecorder {
     channels: Option<u16>,
     samples: Arc<Mutex<Vec<i16>>>,
     last_sound_time: Arc<Mutex<Option<Instant>>>,
-    pub is_recording: bool,
+    is_recording: bool,
 }
 
 impl AudioRecorder {
@@ -128,6 +128,26 @@ impl AudioRecorder {
         Ok(())
     }
 
+    pub fn pause(&self) -> Result<()> {
+        let stream = self
+            .stream
+            .as_ref()
+            .context("'AudioRecorder' is not recording")?;
+        stream.pause()?;
+        Ok(())
+    }
+
+    pub fn resume(&self) -> Result<()> {
+        let stream = self
+            .stream
+            .as_ref()
+            .context("'AudioRecorder' is not recording")?;
+        // The pause shouldn't count as silence
+        *self.last_sound_time.lock().unwrap() = None;
+        stream.play()?;
+        Ok(())
+    }
+
     pub fn stop_recording_and_get_bytes(&mut self) -> Option<Vec<u8>> {
         if !self.is_recording {
             return None;
@@ -209,3 +229,59 @@ impl AudioRecorder {
         }
     }
 }
+
+/// Length of a WAV recording, or `None` if `bytes` isn't one
+pub fn wav_duration(bytes: &[u8]) -> Option<Duration> {
+    let reader = WavReader::new(std::io::Cursor::new(bytes)).ok()?;
+    let sample_rate = reader.spec().sample_rate;
+    if sample_rate == 0 {
+        return None;
+    }
+
+    // `duration` counts samples per channel
+    Some(Duration::from_secs_f64(
+        reader.duration() as f64 / sample_rate as f64,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(channels: u16, sample_rate: u32, samples: usize) -> Vec<u8> {
+        let spec = WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        let mut writer = WavWriter::new(&mut bytes, spec).unwrap();
+        for _ in 0..samples {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn measures_the_length_of_a_recording() {
+        assert_eq!(
+            wav_duration(&wav(1, 16_000, 24_000)),
+            Some(Duration::from_millis(1500))
+        );
+    }
+
+    #[test]
+    fn counts_samples_per_channel() {
+        assert_eq!(
+            wav_duration(&wav(2, 16_000, 32_000)),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn rejects_anything_but_a_wav() {
+        assert_eq!(wav_duration(b"not a recording"), None);
+    }
+}
@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+/// Consecutive failures after which a backend is skipped
+const FAILURE_THRESHOLD: u32 = 3;
+/// How long an open breaker skips its backend before letting a probe through
+const COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Tracks the health of one backend. After `FAILURE_THRESHOLD` consecutive
+/// failures the breaker opens and the backend is skipped. Once `COOLDOWN` has
+/// passed, a single request is let through as a probe: success closes the
+/// breaker, failure keeps it open for another cooldown.
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// When the probe of a half-open breaker was let through. Other requests
+    /// are skipped until it's recorded, or until it's been gone for a whole
+    /// cooldown, e.g. because it was cancelled.
+    probe_started_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn is_open(&self) -> bool {
+        self.opened_at.is_some()
+    }
+
+    /// Whether a request may be sent to the backend. When the breaker is due
+    /// for a probe, the first caller gets to send it and is expected to record
+    /// the result.
+    pub fn allows_request(&mut self) -> bool {
+        let Some(opened_at) = self.opened_at else {
+            return true;
+        };
+
+        let probing = self
+            .probe_started_at
+            .is_some_and(|started_at| started_at.elapsed() < COOLDOWN);
+        if opened_at.elapsed() < COOLDOWN || probing {
+            return false;
+        }
+
+        self.probe_started_at = Some(Instant::now());
+        true
+    }
+
+    pub fn record_success(&mut self) {
+        if self.is_open() {
+            log::info!("Probe succeeded, closing circuit breaker");
+        }
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probe_started_at = None;
+    }
+
+    pub fn record_failure(&mut self) {
+        self.consecutive_failures += 1;
+        self.probe_started_at = None;
+
+        // A failed probe re-opens the breaker straight away
+        if self.is_open() || self.consecutive_failures >= FAILURE_THRESHOLD {
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_breaker() -> CircuitBreaker {
+        let mut breaker = CircuitBreaker::default();
+        for _ in 0..FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        breaker
+    }
+
+    /// Pretends the breaker opened a whole cooldown ago
+    fn cool_down(breaker: &mut CircuitBreaker) {
+        breaker.opened_at = Some(Instant::now() - COOLDOWN);
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let mut breaker = CircuitBreaker::default();
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure();
+            assert!(!breaker.is_open());
+            assert!(breaker.allows_request());
+        }
+
+        breaker.record_failure();
+        assert!(breaker.is_open());
+        assert!(!breaker.allows_request());
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let mut breaker = CircuitBreaker::default();
+        for _ in 1..FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        breaker.record_success();
+        breaker.record_failure();
+
+        assert!(!breaker.is_open());
+    }
+
+    #[test]
+    fn lets_a_single_probe_through_after_the_cooldown() {
+        let mut breaker = open_breaker();
+        cool_down(&mut breaker);
+
+        assert!(breaker.allows_request());
+        assert!(!breaker.allows_request());
+        assert!(!breaker.allows_request());
+    }
+
+    #[test]
+    fn successful_probe_closes_the_breaker() {
+        let mut breaker = open_breaker();
+        cool_down(&mut breaker);
+        assert!(breaker.allows_request());
+
+        breaker.record_success();
+
+        assert!(!breaker.is_open());
+        assert!(breaker.allows_request());
+        assert!(breaker.allows_request());
+    }
+
+    #[test]
+    fn failed_probe_starts_another_cooldown() {
+        let mut breaker = open_breaker();
+        cool_down(&mut breaker);
+        assert!(breaker.allows_request());
+
+        breaker.record_failure();
+
+        assert!(breaker.is_open());
+        assert!(!breaker.allows_request());
+    }
+
+    #[test]
+    fn abandoned_probe_is_replaced_after_a_cooldown() {
+        let mut breaker = open_breaker();
+        cool_down(&mut breaker);
+        assert!(breaker.allows_request());
+
+        breaker.probe_started_at = Some(Instant::now() - COOLDOWN);
+
+        assert!(breaker.allows_request());
+        assert!(!breaker.allows_request());
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use tauri::{AppHandle, image::Image};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// What the clipboard held before a transcript was pasted through it. The
+/// clipboard plugin can't read HTML, so rich text comes back as plain text.
+pub enum ClipboardSnapshot {
+    Text(String),
+    Image(Image<'static>),
+    Empty,
+}
+
+impl ClipboardSnapshot {
+    /// This should not be called on the main thread, reading the clipboard
+    /// there can deadlock on Linux
+    pub fn take(app_handle: &AppHandle) -> Self {
+        let clipboard = app_handle.clipboard();
+
+        if let Some(text) = clipboard.read_text().ok().filter(|t| !t.is_empty()) {
+            return Self::Text(text);
+        }
+        if let Ok(image) = clipboard.read_image() {
+            return Self::Image(image.to_owned());
+        }
+
+        Self::Empty
+    }
+
+    /// Puts the snapshot back, unless the clipboard no longer holds `pasted`,
+    /// in which case something else was copied in the meantime and wins
+    pub fn restore(self, app_handle: &AppHandle, pasted: &str) -> Result<()> {
+        let clipboard = app_handle.clipboard();
+
+        if clipboard.read_text().ok().as_deref() != Some(pasted) {
+            log::info!("Clipboard changed since pasting, not restoring it");
+            return Ok(());
+        }
+
+        self.put_back(app_handle)
+    }
+
+    /// Puts the snapshot back whatever the clipboard holds now
+    pub fn put_back(self, app_handle: &AppHandle) -> Result<()> {
+        let clipboard = app_handle.clipboard();
+
+        match self {
+            Self::Text(text) => clipboard.write_text(text)?,
+            Self::Image(image) => clipboard.write_image(&image)?,
+            Self::Empty => clipboard.clear()?,
+        }
+
+        Ok(())
+    }
+}
@@ -1,16 +1,26 @@
-use crate::{ShortcutsConfig, get_or_create_shortcuts_config};
+use crate::{
+    ShortcutsConfig, get_or_create_shortcuts_config,
+    i18n::{Msg, t},
+};
 use std::{str::FromStr, sync::Mutex};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 
 #[tauri::command]
 pub fn assign_shortcut(app_handle: AppHandle, name: &str, shortcut: &str) -> String {
-    if name != "toggle-recording" && name != "cleanse-clipboard" {
-        return "Invalid shortcut name".into();
+    if name != "toggle-recording"
+        && name != "cleanse-clipboard"
+        && name != "cancel"
+        && name != "polish-selection"
+        && name != "repaste-last"
+        && name != "retry-last"
+        && name != "polish-last-paste"
+    {
+        return t(Msg::InvalidShortcutName).into();
     }
 
     let Ok(shortcut) = Shortcut::from_str(shortcut) else {
-        return "Invalid shortcut".into();
+        return t(Msg::InvalidShortcut).into();
     };
 
     if let Ok(old_shortcuts) = get_or_create_shortcuts_config() {
@@ -22,9 +32,29 @@ pub fn assign_shortcut(app_handle: AppHandle, name: &str, shortcut: &str) -> Str
             _ = app_handle
                 .global_shortcut()
                 .unregister(old_shortcuts.cleanse_clipboard);
+        } else if name == "cancel" {
+            _ = app_handle
+                .global_shortcut()
+                .unregister(old_shortcuts.cancel);
+        } else if name == "polish-selection" {
+            _ = app_handle
+                .global_shortcut()
+                .unregister(old_shortcuts.polish_selection);
+        } else if name == "repaste-last" {
+            _ = app_handle
+                .global_shortcut()
+                .unregister(old_shortcuts.repaste_last);
+        } else if name == "retry-last" {
+            _ = app_handle
+                .global_shortcut()
+                .unregister(old_shortcuts.retry_last);
+        } else if name == "polish-last-paste" {
+            _ = app_handle
+                .global_shortcut()
+                .unregister(old_shortcuts.polish_last_paste);
         }
     } else {
-        return "Failed to parse shortcuts config".into();
+        return t(Msg::ShortcutsConfigFailed).into();
     }
 
     // register the new shortcut
@@ -37,6 +67,16 @@ pub fn assign_shortcut(app_handle: AppHandle, name: &str, shortcut: &str) -> Str
         shortcuts_config.toggle_recording = shortcut;
     } else if name == "cleanse-clipboard" {
         shortcuts_config.cleanse_clipboard = shortcut;
+    } else if name == "cancel" {
+        shortcuts_config.cancel = shortcut;
+    } else if name == "polish-selection" {
+        shortcuts_config.polish_selection = shortcut;
+    } else if name == "repaste-last" {
+        shortcuts_config.repaste_last = shortcut;
+    } else if name == "retry-last" {
+        shortcuts_config.retry_last = shortcut;
+    } else if name == "polish-last-paste" {
+        shortcuts_config.polish_last_paste = shortcut;
     }
 
     // write the new config to disk
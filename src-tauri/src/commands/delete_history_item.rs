@@ -0,0 +1,17 @@
+use crate::{
+    history::History,
+    i18n::{Msg, t},
+};
+use tauri::{AppHandle, Manager};
+
+#[tauri::command]
+pub async fn delete_history_item(app_handle: AppHandle, id: i64) -> String {
+    match app_handle.state::<History>().delete(id) {
+        Ok(true) => "".into(),
+        Ok(false) => t(Msg::HistoryItemNotFound).into(),
+        Err(e) => {
+            log::error!("Failed to delete history item {id}: {e}");
+            t(Msg::DeleteHistoryFailed).into()
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use crate::app_state::{AppState, StateMachine};
+use tauri::{AppHandle, Manager};
+
+/// The current state, for the window to start from before `state-changed`
+/// events arrive
+#[tauri::command]
+pub fn get_app_state(app_handle: AppHandle) -> AppState {
+    app_handle.state::<StateMachine>().current()
+}
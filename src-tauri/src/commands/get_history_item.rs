@@ -0,0 +1,20 @@
+use crate::{
+    history::{History, HistoryItem},
+    i18n::{Msg, t},
+};
+use tauri::{AppHandle, Manager};
+
+#[tauri::command]
+pub async fn get_history_item(
+    app_handle: AppHandle,
+    id: i64,
+) -> Result<HistoryItem, String> {
+    match app_handle.state::<History>().get(id) {
+        Ok(Some(item)) => Ok(item),
+        Ok(None) => Err(t(Msg::HistoryItemNotFound).into()),
+        Err(e) => {
+            log::error!("Failed to get history item {id}: {e}");
+            Err(t(Msg::ReadHistoryFailed).into())
+        }
+    }
+}
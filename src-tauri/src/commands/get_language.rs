@@ -0,0 +1,7 @@
+use crate::i18n::{self, Language};
+
+/// The language of the tray and notifications, so the window can match it
+#[tauri::command]
+pub fn get_language() -> Language {
+    i18n::language()
+}
@@ -1,3 +1,17 @@
 mod assign_shortcut;
+mod delete_history_item;
+mod get_app_state;
+mod get_history_item;
+mod get_language;
+mod retranscribe;
+mod search_history;
+mod set_backend_secret;
 
 pub use assign_shortcut::*;
+pub use delete_history_item::*;
+pub use get_app_state::*;
+pub use get_history_item::*;
+pub use get_language::*;
+pub use retranscribe::*;
+pub use search_history::*;
+pub use set_backend_secret::*;
@@ -0,0 +1,103 @@
+use crate::{
+    audio_archive, audio_recorder,
+    config::AppConfig,
+    history::{History, HistoryItem, HistoryKind, NewHistoryItem},
+    i18n::{Msg, t},
+    transcribe_client::TranscribeClient,
+    vocabulary,
+};
+use chrono::Local;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Sends the archived recording of a history item to `backend`, or down the
+/// backend chain if it's `None`. `language` is an ISO-639-1 code, detected by
+/// the backend if it's `None`. The result becomes a new history item.
+#[tauri::command]
+pub async fn retranscribe(
+    app_handle: AppHandle,
+    id: i64,
+    backend: Option<String>,
+    language: Option<String>,
+) -> Result<HistoryItem, String> {
+    let recording = audio_archive::load(id).map_err(|e| {
+        log::error!("Failed to load recording for item {id}: {e}");
+        t(Msg::NoArchivedRecording).to_string()
+    })?;
+
+    let config = app_handle
+        .state::<Mutex<AppConfig>>()
+        .lock()
+        .unwrap()
+        .clone();
+    let backend = backend.filter(|name| !name.is_empty());
+    let language = language.filter(|language| !language.is_empty());
+
+    let duration = audio_recorder::wav_duration(&recording);
+    let started_at = Local::now();
+
+    let client = app_handle.state::<TranscribeClient>();
+    let result = match &backend {
+        Some(name) => {
+            let Some(backend) = config
+                .transcription_backends
+                .iter()
+                .find(|backend| &backend.backend.name == name)
+            else {
+                return Err(format!("{} '{name}'", t(Msg::UnknownBackend)));
+            };
+            client
+                .fetch_transcription(
+                    backend,
+                    recording,
+                    &config.vocabulary,
+                    language.as_deref(),
+                    false,
+                )
+                .await
+        }
+        None => {
+            client
+                .transcribe(
+                    &config.transcription_backends,
+                    recording,
+                    &config.vocabulary,
+                    language.as_deref(),
+                    false,
+                )
+                .await
+        }
+    };
+
+    let mut transcription = result.map_err(|e| {
+        log::error!("Failed to re-transcribe item {id}: {e}");
+        t(Msg::RetranscribeFailed).to_string()
+    })?;
+    transcription.text =
+        vocabulary::apply_vocabulary(&transcription.text, &config.vocabulary);
+
+    let history = app_handle.state::<History>();
+    let item = NewHistoryItem {
+        kind: HistoryKind::Transcription,
+        raw_text: &transcription.text,
+        polished_text: None,
+        duration,
+        backend: Some(&transcription.backend),
+        language: language.as_deref().or(transcription.language.as_deref()),
+        sinks: Vec::new(),
+        started_at,
+    };
+    let new_id = history.record(&item).map_err(|e| {
+        log::error!("Failed to record re-transcription in history: {e}");
+        t(Msg::SaveTranscriptionFailed).to_string()
+    })?;
+
+    if let Err(e) = audio_archive::copy(id, new_id, &config.archive) {
+        log::error!("Failed to archive recording for item {new_id}: {e}");
+    }
+
+    match history.get(new_id) {
+        Ok(Some(item)) => Ok(item),
+        _ => Err(t(Msg::ReadTranscriptionFailed).into()),
+    }
+}
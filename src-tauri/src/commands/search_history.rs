@@ -0,0 +1,22 @@
+use crate::{
+    history::{History, HistoryItem},
+    i18n::{Msg, t},
+};
+use tauri::{AppHandle, Manager};
+
+/// Full-text search over past transcriptions and polishes. An empty `query`
+/// lists the most recent ones.
+#[tauri::command]
+pub async fn search_history(
+    app_handle: AppHandle,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<HistoryItem>, String> {
+    app_handle
+        .state::<History>()
+        .search(&query, limit.unwrap_or(50))
+        .map_err(|e| {
+            log::error!("Failed to search history: {e}");
+            t(Msg::SearchHistoryFailed).to_string()
+        })
+}
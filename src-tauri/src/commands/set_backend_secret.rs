@@ -0,0 +1,25 @@
+use crate::{
+    i18n::{Msg, t},
+    secrets,
+};
+
+/// Stores the token or API key for `backend`. An empty `secret` removes it.
+#[tauri::command]
+pub fn set_backend_secret(backend: &str, secret: &str) -> String {
+    if backend.is_empty() {
+        return t(Msg::InvalidBackendName).into();
+    }
+
+    let result = if secret.is_empty() {
+        secrets::delete_secret(backend)
+    } else {
+        secrets::set_secret(backend, secret)
+    };
+
+    if let Err(e) = result {
+        log::error!("Failed to store secret for backend '{backend}': {e}");
+        return t(Msg::StoreSecretFailed).into();
+    }
+
+    "".into()
+}
@@ -0,0 +1,567 @@
+use crate::{
+    constants::API_BASE_URL, i18n::Language, keystrokes::Chord, output_sink::OutputSink,
+};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+use tauri_plugin_global_shortcut::Shortcut;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Product names, people and jargon that should always be spelled exactly
+    /// like this. Sent to the backend as a hint and enforced locally afterwards.
+    pub vocabulary: Vec<String>,
+    /// Tried in order until one of them succeeds
+    pub transcription_backends: Vec<TranscriptionBackendConfig>,
+    pub polishing: PolishingConfig,
+    pub presets: Vec<PolishPreset>,
+    /// Types polished text into the focused window as it streams in instead of
+    /// pasting it at the end. Needs a `chat_completions` backend with `stream`.
+    pub progressive_paste: bool,
+    pub translations: Vec<TranslationConfig>,
+    /// Apps that don't paste with the platform's default shortcut
+    pub paste_overrides: Vec<PasteOverride>,
+    pub output: OutputConfig,
+    pub archive: ArchiveConfig,
+    /// How many transcripts the tray's "Recent" submenu lists
+    pub recent_count: usize,
+    pub icons: IconConfig,
+    pub sounds: SoundConfig,
+    pub notifications: NotificationConfig,
+    /// Language of the tray menu and notifications, `en` or `es`. Follows the
+    /// system locale if it isn't set.
+    pub language: Option<Language>,
+}
+
+impl AppConfig {
+    /// Name of the backend `TranscribeClient::polish` sends text to
+    pub fn polishing_backend(&self, preset: Option<&PolishPreset>) -> Option<String> {
+        if let Some(preset) = preset {
+            return preset
+                .resolve(&self.polishing)
+                .ok()
+                .map(|chat| chat.backend.name);
+        }
+
+        match &self.polishing {
+            PolishingConfig::Whistle => self
+                .transcription_backends
+                .iter()
+                .find(|backend| backend.api == TranscriptionApi::Whistle)
+                .map(|backend| backend.backend.name.clone()),
+            PolishingConfig::ChatCompletions(chat) => Some(chat.backend.name.clone()),
+        }
+    }
+
+    /// Whether `TranscribeClient::polish` hands out the reply while it's being
+    /// generated, which is what typing it progressively relies on
+    pub fn streams_polish(&self, preset: Option<&PolishPreset>) -> bool {
+        if let Some(preset) = preset {
+            return preset
+                .resolve(&self.polishing)
+                .is_ok_and(|chat| chat.stream);
+        }
+
+        match &self.polishing {
+            PolishingConfig::Whistle => false,
+            PolishingConfig::ChatCompletions(chat) => chat.stream,
+        }
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            vocabulary: Vec::new(),
+            transcription_backends: vec![TranscriptionBackendConfig::default()],
+            polishing: PolishingConfig::default(),
+            presets: Vec::new(),
+            progressive_paste: false,
+            translations: Vec::new(),
+            paste_overrides: Vec::new(),
+            output: OutputConfig::default(),
+            archive: ArchiveConfig::default(),
+            recent_count: 5,
+            icons: IconConfig::default(),
+            sounds: SoundConfig::default(),
+            notifications: NotificationConfig::default(),
+            language: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TranscriptionBackendConfig {
+    #[serde(flatten)]
+    pub backend: BackendConfig,
+    #[serde(default)]
+    pub api: TranscriptionApi,
+    /// Only used by the `openai` API, defaults to `whisper-1`
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionApi {
+    /// Our own server: `/transcribe-auto-detect` and `/clean-transcription`
+    #[default]
+    Whistle,
+    /// `/audio/transcriptions`, as served by OpenAI, Groq or a local whisper.cpp server
+    #[serde(rename = "openai")]
+    OpenAi,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackendConfig {
+    /// Identifies the backend, and the secret stored for it.
+    pub name: String,
+    pub base_url: String,
+    /// How to present the stored secret. `None` sends unauthenticated requests.
+    #[serde(default)]
+    pub auth: Option<AuthScheme>,
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self {
+            name: "whistle".into(),
+            base_url: API_BASE_URL.into(),
+            auth: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <secret>`
+    Bearer,
+    /// `<name>: <secret>`, e.g. `X-API-Key`
+    Header { name: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolishingConfig {
+    /// The `/clean-transcription` endpoint of the first `whistle` transcription backend
+    #[default]
+    Whistle,
+    /// Any server that speaks the OpenAI `/chat/completions` protocol
+    ChatCompletions(ChatCompletionsConfig),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatCompletionsConfig {
+    /// `base_url` includes the version prefix, e.g. `https://api.deepseek.com/v1`
+    pub backend: BackendConfig,
+    pub model: String,
+    #[serde(default = "default_system_prompt")]
+    pub system_prompt: String,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Receive the reply as server-sent events while it is being generated
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// A named way of polishing, e.g. "Email" or "Bullet points". Unset fields
+/// fall back to the `chat_completions` polishing config.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PolishPreset {
+    pub name: String,
+    pub system_prompt: String,
+    #[serde(default)]
+    pub shortcut: Option<Shortcut>,
+    #[serde(default)]
+    pub backend: Option<BackendConfig>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+impl PolishPreset {
+    pub fn resolve(&self, polishing: &PolishingConfig) -> Result<ChatCompletionsConfig> {
+        let base = match polishing {
+            PolishingConfig::ChatCompletions(chat) => Some(chat),
+            PolishingConfig::Whistle => None,
+        };
+
+        let backend = self
+            .backend
+            .clone()
+            .or_else(|| base.map(|chat| chat.backend.clone()))
+            .with_context(|| {
+                format!("Preset '{}' has no backend configured", self.name)
+            })?;
+        let model = self
+            .model
+            .clone()
+            .or_else(|| base.map(|chat| chat.model.clone()))
+            .with_context(|| format!("Preset '{}' has no model configured", self.name))?;
+
+        Ok(ChatCompletionsConfig {
+            backend,
+            model,
+            system_prompt: self.system_prompt.clone(),
+            temperature: self.temperature.or(base.and_then(|chat| chat.temperature)),
+            max_tokens: self.max_tokens.or(base.and_then(|chat| chat.max_tokens)),
+            stream: base.is_some_and(|chat| chat.stream),
+        })
+    }
+}
+
+/// A shortcut that dictates in one language and outputs another
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TranslationConfig {
+    pub shortcut: Shortcut,
+    /// e.g. "English" or "German"
+    pub target_language: String,
+    #[serde(default)]
+    pub method: TranslationMethod,
+    /// Overrides the global sinks for this shortcut when not empty
+    #[serde(default)]
+    pub output: Vec<OutputSink>,
+}
+
+impl TranslationConfig {
+    /// Whether `method` can translate into `target_language` at all
+    pub fn is_supported(&self) -> bool {
+        self.method != TranslationMethod::Backend || is_english(&self.target_language)
+    }
+}
+
+fn is_english(language: &str) -> bool {
+    matches!(language.trim().to_lowercase().as_str(), "english" | "en")
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationMethod {
+    /// Translate the transcript with the `chat_completions` polishing backend
+    #[default]
+    Polishing,
+    /// Let the transcription backend translate. Whisper only translates into English.
+    Backend,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Every transcription is delivered to each of these, in order
+    pub sinks: Vec<OutputSink>,
+    /// Pause after each typed character. Some apps drop keys that arrive too fast.
+    pub key_delay_ms: u64,
+    /// Puts back what was in the clipboard before a transcript was pasted
+    /// through it
+    pub restore_clipboard: bool,
+    /// How long to wait after pasting before restoring, so the target app has
+    /// read the transcript
+    pub restore_delay_ms: u64,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            sinks: vec![OutputSink::Clipboard],
+            key_delay_ms: 5,
+            restore_clipboard: false,
+            restore_delay_ms: 500,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    /// Keeps every recording so it can be re-transcribed later
+    pub enabled: bool,
+    /// `None` keeps recordings regardless of age
+    pub max_age_days: Option<u32>,
+    /// `None` lets the archive grow without limit
+    pub max_total_mb: Option<u64>,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_days: Some(30),
+            max_total_mb: Some(500),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct IconConfig {
+    /// Name of a folder in `~/.config/whistle/icons` with PNGs replacing the
+    /// bundled tray icons
+    pub theme: Option<String>,
+    /// Spins the icon while waiting on a backend
+    pub animate: bool,
+    pub frame_ms: u64,
+}
+
+impl Default for IconConfig {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            animate: false,
+            frame_ms: 120,
+        }
+    }
+}
+
+/// A sound for each step of a dictation
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SoundConfig {
+    pub start: CueConfig,
+    pub stop: CueConfig,
+    pub success: CueConfig,
+    pub error: CueConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CueConfig {
+    pub enabled: bool,
+    /// From 0.0 to 1.0
+    pub volume: f32,
+    /// A WAV file played instead of the bundled sound
+    pub path: Option<PathBuf>,
+}
+
+impl Default for CueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: 0.5,
+            path: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+/// Notifications only get "Retry" and "Copy original" buttons on Linux. The
+/// notification plugin supports actions on mobile only, so elsewhere they're
+/// shown without buttons.
+pub struct NotificationConfig {
+    /// Silences every notification, also toggled from the tray, which saves it
+    /// with `save_do_not_disturb`
+    pub do_not_disturb: bool,
+    /// Shows the start of the transcribed or polished text in the notification
+    pub preview: bool,
+    /// Characters of the preview before it's cut off
+    pub preview_length: usize,
+    pub events: NotificationEvents,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            do_not_disturb: false,
+            preview: false,
+            preview_length: 80,
+            events: NotificationEvents::default(),
+        }
+    }
+}
+
+/// Which notifications are shown
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NotificationEvents {
+    pub start_polishing: bool,
+    pub polish_success: bool,
+    pub transcribe_success: bool,
+    pub transcription_error: bool,
+    pub api_error: bool,
+    pub accessibility_error: bool,
+    pub cancelled_silence: bool,
+    pub nothing_selected: bool,
+    pub nothing_to_repeat: bool,
+    pub empty_clipboard: bool,
+}
+
+impl Default for NotificationEvents {
+    fn default() -> Self {
+        Self {
+            start_polishing: true,
+            polish_success: true,
+            transcribe_success: true,
+            transcription_error: true,
+            api_error: true,
+            accessibility_error: true,
+            cancelled_silence: true,
+            nothing_selected: true,
+            nothing_to_repeat: true,
+            empty_clipboard: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PasteOverride {
+    /// Name of the focused application, matched case-insensitively
+    pub app: String,
+    /// e.g. `Ctrl+Shift+V` or `Shift+Insert`
+    pub keys: Chord,
+}
+
+fn default_system_prompt() -> String {
+    "You clean up dictated text. Fix punctuation, capitalization, grammar and obvious \
+     transcription mistakes without changing the meaning or the language. Reply with \
+     the cleaned text only."
+        .into()
+}
+
+/// Allows paths like `~/notes/dictation.md` in the config
+pub fn expand_home(path: &Path) -> Result<PathBuf> {
+    match path.strip_prefix("~") {
+        Ok(relative) => Ok(dirs::home_dir()
+            .context("Could not find home directory")?
+            .join(relative)),
+        Err(_) => Ok(path.to_path_buf()),
+    }
+}
+
+fn app_config_path() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .context("Could not find home directory")?
+        .join(".config/whistle/config.json"))
+}
+
+pub fn get_or_create_app_config() -> Result<AppConfig> {
+    let config_path = app_config_path()?;
+
+    // create the parent directories if they don't exist
+    let parent_dir = config_path.parent().context("Could not find config directory")?;
+    std::fs::create_dir_all(parent_dir)?;
+
+    if !config_path.exists() {
+        let config = AppConfig::default();
+        let file_contents = serde_json::to_string_pretty(&config)?;
+        std::fs::write(config_path, file_contents)?;
+        return Ok(config);
+    }
+
+    let file_contents = read_to_string(config_path)?;
+    let config: AppConfig = serde_json::from_str(&file_contents)?;
+
+    for translation in &config.translations {
+        if !translation.is_supported() {
+            log::warn!(
+                "The transcription backend only translates into English, so the '{}' \
+                 translation will come out in English. Use the \"polishing\" method instead.",
+                translation.target_language
+            );
+        }
+    }
+
+    Ok(config)
+}
+
+/// Writes the tray's do not disturb toggle to the config file. Only that field
+/// is touched, so the rest of the file stays as the user wrote it.
+pub fn save_do_not_disturb(do_not_disturb: bool) -> Result<()> {
+    let config_path = app_config_path()?;
+    let mut config: serde_json::Value =
+        serde_json::from_str(&read_to_string(&config_path)?)?;
+
+    let Some(config_object) = config.as_object_mut() else {
+        bail!("{} is not a JSON object", config_path.display());
+    };
+    let notifications = config_object
+        .entry("notifications")
+        .or_insert_with(|| serde_json::json!({}));
+    let Some(notifications) = notifications.as_object_mut() else {
+        bail!(
+            "'notifications' in {} is not an object",
+            config_path.display()
+        );
+    };
+    notifications.insert("do_not_disturb".into(), do_not_disturb.into());
+
+    std::fs::write(config_path, serde_json::to_string_pretty(&config)?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backend_names(config: &AppConfig) -> Vec<&str> {
+        config
+            .transcription_backends
+            .iter()
+            .map(|backend| backend.backend.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn reads_a_chain_of_backends() {
+        let config: AppConfig = serde_json::from_str(
+            r#"{
+                "transcription_backends": [
+                    { "name": "local", "base_url": "http://localhost:8080" },
+                    { "name": "groq", "base_url": "https://api.groq.com/openai/v1", "api": "openai" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(backend_names(&config), ["local", "groq"]);
+        assert_eq!(
+            config.transcription_backends[1].api,
+            TranscriptionApi::OpenAi
+        );
+    }
+
+    #[test]
+    fn reads_a_list_of_sinks() {
+        let config: AppConfig = serde_json::from_str(
+            r#"{ "output": { "sinks": ["type", { "file": "~/notes.md" }] } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.output.sinks,
+            [OutputSink::Type, OutputSink::File("~/notes.md".into())]
+        );
+    }
+
+    #[test]
+    fn defaults_to_the_clipboard() {
+        let config: AppConfig = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(config.output.sinks, [OutputSink::Clipboard]);
+    }
+
+    #[test]
+    fn defaults_to_one_backend() {
+        let config: AppConfig = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(backend_names(&config), ["whistle"]);
+    }
+
+    #[test]
+    fn recognizes_english_as_a_target_language() {
+        assert!(is_english("English"));
+        assert!(is_english(" en "));
+        assert!(!is_english("German"));
+        assert!(!is_english("es"));
+    }
+}
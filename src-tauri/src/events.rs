@@ -0,0 +1,62 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Why a run of the pipeline failed, so the window doesn't have to parse
+/// messages
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    RecordingFailed,
+    TranscriptionFailed,
+    TranslationFailed,
+    PolishFailed,
+    DeliveryFailed,
+    EmptyClipboard,
+    NothingSelected,
+    NothingToRepeat,
+}
+
+/// Sent to the window as the `pipeline` event, tagged with `kind`, next to the
+/// `state-changed` event the state machine sends
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum PipelineEvent {
+    RecordingStarted,
+    RecordingStopped {
+        duration_ms: Option<u64>,
+    },
+    TranscriptionStarted,
+    TranscriptionResult {
+        text: String,
+        translation: Option<String>,
+        backend: String,
+    },
+    PolishStarted,
+    PolishResult {
+        original: String,
+        text: String,
+    },
+    Error {
+        code: ErrorCode,
+        message: String,
+    },
+}
+
+impl PipelineEvent {
+    pub fn error(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Error {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn emit(self, app_handle: &AppHandle) {
+        if let Err(e) = app_handle.emit("pipeline", self) {
+            log::error!("Failed to emit pipeline event: {}", e);
+        }
+    }
+}
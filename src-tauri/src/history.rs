@@ -0,0 +1,255 @@
+use crate::audio_archive;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use serde::Serialize;
+use std::{sync::Mutex, time::Duration};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS history (
+        id INTEGER PRIMARY KEY,
+        kind TEXT NOT NULL,
+        raw_text TEXT NOT NULL,
+        polished_text TEXT,
+        duration_ms INTEGER,
+        backend TEXT,
+        language TEXT,
+        sinks TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+
+    CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+        raw_text, polished_text, content = 'history', content_rowid = 'id'
+    );
+
+    CREATE TRIGGER IF NOT EXISTS history_after_insert AFTER INSERT ON history BEGIN
+        INSERT INTO history_fts (rowid, raw_text, polished_text)
+        VALUES (new.id, new.raw_text, new.polished_text);
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS history_after_delete AFTER DELETE ON history BEGIN
+        INSERT INTO history_fts (history_fts, rowid, raw_text, polished_text)
+        VALUES ('delete', old.id, old.raw_text, old.polished_text);
+    END;
+";
+
+const COLUMNS: &str = "history.id, kind, history.raw_text, history.polished_text, \
+                       duration_ms, backend, language, sinks, started_at, created_at";
+
+#[derive(Debug, Clone, Copy)]
+pub enum HistoryKind {
+    Transcription,
+    Translation,
+    Polish,
+}
+
+impl HistoryKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Transcription => "transcription",
+            Self::Translation => "translation",
+            Self::Polish => "polish",
+        }
+    }
+}
+
+/// A transcription or polish about to be recorded
+pub struct NewHistoryItem<'a> {
+    pub kind: HistoryKind,
+    pub raw_text: &'a str,
+    /// The polished text, or the translation for translations
+    pub polished_text: Option<&'a str>,
+    /// Length of the recording
+    pub duration: Option<Duration>,
+    pub backend: Option<&'a str>,
+    /// The target language for translations, otherwise the spoken language if
+    /// the backend reported it
+    pub language: Option<&'a str>,
+    /// Where the result was sent, e.g. `clipboard` or `file`
+    pub sinks: Vec<&'a str>,
+    /// When the request to the backend was made
+    pub started_at: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItem {
+    pub id: i64,
+    /// `transcription`, `translation` or `polish`
+    pub kind: String,
+    pub raw_text: String,
+    pub polished_text: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub backend: Option<String>,
+    pub language: Option<String>,
+    pub sinks: Vec<String>,
+    /// Whether the recording was archived and can be re-transcribed
+    pub has_recording: bool,
+    /// RFC 3339
+    pub started_at: String,
+    /// RFC 3339
+    pub created_at: String,
+}
+
+impl HistoryItem {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let id = row.get(0)?;
+        let sinks: String = row.get(7)?;
+
+        Ok(Self {
+            id,
+            kind: row.get(1)?,
+            raw_text: row.get(2)?,
+            polished_text: row.get(3)?,
+            duration_ms: row.get(4)?,
+            backend: row.get(5)?,
+            language: row.get(6)?,
+            sinks: sinks
+                .split(',')
+                .filter(|sink| !sink.is_empty())
+                .map(String::from)
+                .collect(),
+            has_recording: audio_archive::has_recording(id),
+            started_at: row.get(8)?,
+            created_at: row.get(9)?,
+        })
+    }
+}
+
+/// Every transcription and polish, kept in `~/.config/whistle/history.db`
+pub struct History(Mutex<Connection>);
+
+impl History {
+    pub fn open() -> Result<Self> {
+        let db_path = dirs::home_dir()
+            .context("Could not find home directory")?
+            .join(".config/whistle/history.db");
+
+        let parent_dir = db_path
+            .parent()
+            .context("Could not find config directory")?;
+        std::fs::create_dir_all(parent_dir)?;
+
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open {}", db_path.display()))?;
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self(Mutex::new(conn)))
+    }
+
+    /// Returns the id of the new item
+    pub fn record(&self, item: &NewHistoryItem) -> Result<i64> {
+        let conn = self.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO history (kind, raw_text, polished_text, duration_ms, backend, \
+             language, sinks, started_at, created_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                item.kind.as_str(),
+                item.raw_text,
+                item.polished_text,
+                item.duration.map(|d| d.as_millis() as u64),
+                item.backend,
+                item.language,
+                item.sinks.join(","),
+                item.started_at.to_rfc3339(),
+                Local::now().to_rfc3339(),
+            ],
+        )?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Full-text search over raw and polished text, best matches first. An
+    /// empty query returns the most recent items.
+    pub fn search(&self, query: &str, limit: u32) -> Result<Vec<HistoryItem>> {
+        let conn = self.0.lock().unwrap();
+
+        let Some(query) = fts_query(query) else {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {COLUMNS} FROM history ORDER BY id DESC LIMIT ?1"
+            ))?;
+            let items = stmt
+                .query_map(params![limit], HistoryItem::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            return Ok(items);
+        };
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {COLUMNS} FROM history_fts \
+             JOIN history ON history.id = history_fts.rowid \
+             WHERE history_fts MATCH ?1 ORDER BY rank LIMIT ?2"
+        ))?;
+        let items = stmt
+            .query_map(params![query, limit], HistoryItem::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<HistoryItem>> {
+        let conn = self.0.lock().unwrap();
+        let item = conn
+            .query_row(
+                &format!("SELECT {COLUMNS} FROM history WHERE id = ?1"),
+                params![id],
+                HistoryItem::from_row,
+            )
+            .optional()?;
+
+        Ok(item)
+    }
+
+    /// Deletes the item and its archived recording. Returns whether an item was
+    /// deleted.
+    pub fn delete(&self, id: i64) -> Result<bool> {
+        let conn = self.0.lock().unwrap();
+        let deleted = conn.execute("DELETE FROM history WHERE id = ?1", params![id])?;
+        audio_archive::delete(id)?;
+
+        Ok(deleted > 0)
+    }
+}
+
+/// Turns what the user typed into an FTS5 query that matches every word as a
+/// prefix. Quoting each word keeps FTS5 operators and punctuation literal.
+fn fts_query(query: &str) -> Option<String> {
+    let terms = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_every_word_as_a_prefix() {
+        assert_eq!(
+            fts_query("  hello   wor ").as_deref(),
+            Some("\"hello\"* \"wor\"*")
+        );
+    }
+
+    #[test]
+    fn keeps_operators_and_quotes_literal() {
+        assert_eq!(
+            fts_query("NOT a\"b -c*").as_deref(),
+            Some("\"NOT\"* \"a\"\"b\"* \"-c*\"*")
+        );
+    }
+
+    #[test]
+    fn blank_queries_have_no_match_expression() {
+        assert_eq!(fts_query(""), None);
+        assert_eq!(fts_query(" \t\n"), None);
+    }
+}
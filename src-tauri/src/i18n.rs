@@ -0,0 +1,296 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+impl Language {
+    /// The language of the system locale, English if it isn't translated
+    pub fn detect() -> Self {
+        sys_locale::get_locale()
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// `locale` is a tag like `es-MX`, `es_ES.UTF-8` or `en`
+    fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale.split(['-', '_', '.']).next()?;
+        match code.to_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+}
+
+/// Sets the language of every message for the rest of the run, `None` uses
+/// the system locale
+pub fn init(language: Option<Language>) -> Language {
+    let language = language.unwrap_or_else(Language::detect);
+    *LANGUAGE.get_or_init(|| language)
+}
+
+/// The language picked by `init`
+pub fn language() -> Language {
+    LANGUAGE.get().copied().unwrap_or_default()
+}
+
+/// `msg` in the language picked by `init`
+pub fn t(msg: Msg) -> &'static str {
+    match language() {
+        Language::English => msg.english(),
+        Language::Spanish => msg.spanish(),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Msg {
+    // Tray
+    StartRecording,
+    StopRecording,
+    PauseRecording,
+    ResumeRecording,
+    /// Next to the recording timer
+    Paused,
+    Cancel,
+    PolishClipboard,
+    PolishSelection,
+    PolishWith,
+    CopyLastTranscript,
+    RetryLastRecording,
+    Recent,
+    ClearRecent,
+    /// Followed by the name of the backend
+    Backend,
+    BackendChecking,
+    BackendNone,
+    DoNotDisturb,
+    OpenWindow,
+    QuitApp,
+
+    // Notifications
+    Error,
+    PolishSuccessTitle,
+    PolishSuccessBody,
+    StartPolishingTitle,
+    StartPolishingBody,
+    TranscribeSuccessTitle,
+    TranscribeSuccessBody,
+    TranscriptionErrorBody,
+    ApiErrorBody,
+    AccessibilityErrorBody,
+    CancelledSilenceTitle,
+    CancelledSilenceBody,
+    NothingSelectedTitle,
+    NothingSelectedBody,
+    NothingToRepeatTitle,
+    NothingToRepeatBody,
+    EmptyClipboardTitle,
+    EmptyClipboardBody,
+    Retry,
+    CopyOriginal,
+
+    // Pipeline errors
+    NothingRecorded,
+    NoTranscriptToPaste,
+    NoRecordingToRetry,
+    NoTranscriptToPolish,
+    LastTranscriptNotPasted,
+    NothingSelectedToPolish,
+    ClipboardEmpty,
+
+    // Commands
+    InvalidShortcutName,
+    InvalidShortcut,
+    ShortcutsConfigFailed,
+    InvalidBackendName,
+    StoreSecretFailed,
+    HistoryItemNotFound,
+    ReadHistoryFailed,
+    SearchHistoryFailed,
+    DeleteHistoryFailed,
+    NoArchivedRecording,
+    /// Followed by the name of the backend
+    UnknownBackend,
+    RetranscribeFailed,
+    SaveTranscriptionFailed,
+    ReadTranscriptionFailed,
+}
+
+impl Msg {
+    fn english(self) -> &'static str {
+        match self {
+            Msg::StartRecording => "Start recording",
+            Msg::StopRecording => "Stop & transcribe",
+            Msg::PauseRecording => "Pause recording",
+            Msg::ResumeRecording => "Resume recording",
+            Msg::Paused => "paused",
+            Msg::Cancel => "Cancel",
+            Msg::PolishClipboard => "Polish clipboard",
+            Msg::PolishSelection => "Polish selection",
+            Msg::PolishWith => "Polish with",
+            Msg::CopyLastTranscript => "Copy last transcript",
+            Msg::RetryLastRecording => "Retry last recording",
+            Msg::Recent => "Recent",
+            Msg::ClearRecent => "Clear recent",
+            Msg::Backend => "Backend",
+            Msg::BackendChecking => "Backend: checking...",
+            Msg::BackendNone => "Backend: none available",
+            Msg::DoNotDisturb => "Do not disturb",
+            Msg::OpenWindow => "Open Window",
+            Msg::QuitApp => "Quit app",
+
+            Msg::Error => "Error",
+            Msg::PolishSuccessTitle => "Polishing complete",
+            Msg::PolishSuccessBody => "Your polished text is ready and in your clipboard",
+            Msg::StartPolishingTitle => "Loading...",
+            Msg::StartPolishingBody => "We're starting to polish your text. Please wait",
+            Msg::TranscribeSuccessTitle => "Transcription complete",
+            Msg::TranscribeSuccessBody => "Your transcription is ready in your clipboard",
+            Msg::TranscriptionErrorBody => {
+                "Failed to transcribe your recording. Retry it from the tray menu"
+            }
+            Msg::ApiErrorBody => "Failed to connect to the API. Please try again later",
+            Msg::AccessibilityErrorBody => {
+                "Please grant accessibility permissions to the app and restart it"
+            }
+            Msg::CancelledSilenceTitle => "Recording cancelled",
+            Msg::CancelledSilenceBody => {
+                "No sound detected for a while, recording cancelled"
+            }
+            Msg::NothingSelectedTitle => "Nothing selected",
+            Msg::NothingSelectedBody => {
+                "Select the text you want to polish and try again"
+            }
+            Msg::NothingToRepeatTitle => "Nothing to repeat",
+            Msg::NothingToRepeatBody => "Record something first",
+            Msg::EmptyClipboardTitle => "Empty clipboard",
+            Msg::EmptyClipboardBody => {
+                "We couldn't find any text in your clipboard to polish"
+            }
+            Msg::Retry => "Retry",
+            Msg::CopyOriginal => "Copy original",
+
+            Msg::NothingRecorded => "Nothing was recorded",
+            Msg::NoTranscriptToPaste => "No transcript to paste again",
+            Msg::NoRecordingToRetry => "No recording to retry",
+            Msg::NoTranscriptToPolish => "No transcript to polish",
+            Msg::LastTranscriptNotPasted => {
+                "The last transcript wasn't pasted, so there's nothing to replace"
+            }
+            Msg::NothingSelectedToPolish => "Nothing selected to polish",
+            Msg::ClipboardEmpty => "The clipboard is empty",
+
+            Msg::InvalidShortcutName => "Invalid shortcut name",
+            Msg::InvalidShortcut => "Invalid shortcut",
+            Msg::ShortcutsConfigFailed => "Failed to parse shortcuts config",
+            Msg::InvalidBackendName => "Invalid backend name",
+            Msg::StoreSecretFailed => "Failed to store secret",
+            Msg::HistoryItemNotFound => "History item not found",
+            Msg::ReadHistoryFailed => "Failed to read history",
+            Msg::SearchHistoryFailed => "Failed to search history",
+            Msg::DeleteHistoryFailed => "Failed to delete history item",
+            Msg::NoArchivedRecording => "No recording archived for this item",
+            Msg::UnknownBackend => "Unknown backend",
+            Msg::RetranscribeFailed => "Failed to transcribe the recording",
+            Msg::SaveTranscriptionFailed => "Failed to save the transcription",
+            Msg::ReadTranscriptionFailed => "Failed to read the transcription back",
+        }
+    }
+
+    fn spanish(self) -> &'static str {
+        match self {
+            Msg::StartRecording => "Iniciar grabación",
+            Msg::StopRecording => "Detener y transcribir",
+            Msg::PauseRecording => "Pausar grabación",
+            Msg::ResumeRecording => "Reanudar grabación",
+            Msg::Paused => "en pausa",
+            Msg::Cancel => "Cancelar",
+            Msg::PolishClipboard => "Pulir portapapeles",
+            Msg::PolishSelection => "Pulir selección",
+            Msg::PolishWith => "Pulir con",
+            Msg::CopyLastTranscript => "Copiar última transcripción",
+            Msg::RetryLastRecording => "Reintentar última grabación",
+            Msg::Recent => "Recientes",
+            Msg::ClearRecent => "Borrar recientes",
+            Msg::Backend => "Servidor",
+            Msg::BackendChecking => "Servidor: comprobando...",
+            Msg::BackendNone => "Servidor: ninguno disponible",
+            Msg::DoNotDisturb => "No molestar",
+            Msg::OpenWindow => "Abrir ventana",
+            Msg::QuitApp => "Salir",
+
+            Msg::Error => "Error",
+            Msg::PolishSuccessTitle => "Texto pulido",
+            Msg::PolishSuccessBody => "Tu texto pulido está listo en el portapapeles",
+            Msg::StartPolishingTitle => "Cargando...",
+            Msg::StartPolishingBody => "Estamos puliendo tu texto. Espera un momento",
+            Msg::TranscribeSuccessTitle => "Transcripción lista",
+            Msg::TranscribeSuccessBody => {
+                "Tu transcripción está lista en el portapapeles"
+            }
+            Msg::TranscriptionErrorBody => {
+                "No se pudo transcribir la grabación. Reinténtalo desde el menú de la bandeja"
+            }
+            Msg::ApiErrorBody => {
+                "No se pudo conectar con la API. Inténtalo de nuevo más tarde"
+            }
+            Msg::AccessibilityErrorBody => {
+                "Concede permisos de accesibilidad a la aplicación y reiníciala"
+            }
+            Msg::CancelledSilenceTitle => "Grabación cancelada",
+            Msg::CancelledSilenceBody => {
+                "No se detectó sonido durante un rato, grabación cancelada"
+            }
+            Msg::NothingSelectedTitle => "Nada seleccionado",
+            Msg::NothingSelectedBody => {
+                "Selecciona el texto que quieres pulir e inténtalo de nuevo"
+            }
+            Msg::NothingToRepeatTitle => "Nada que repetir",
+            Msg::NothingToRepeatBody => "Graba algo primero",
+            Msg::EmptyClipboardTitle => "Portapapeles vacío",
+            Msg::EmptyClipboardBody => {
+                "No encontramos ningún texto en el portapapeles para pulir"
+            }
+            Msg::Retry => "Reintentar",
+            Msg::CopyOriginal => "Copiar original",
+
+            Msg::NothingRecorded => "No se grabó nada",
+            Msg::NoTranscriptToPaste => {
+                "No hay ninguna transcripción para pegar de nuevo"
+            }
+            Msg::NoRecordingToRetry => "No hay ninguna grabación para reintentar",
+            Msg::NoTranscriptToPolish => "No hay ninguna transcripción para pulir",
+            Msg::LastTranscriptNotPasted => {
+                "La última transcripción no se pegó, así que no hay nada que reemplazar"
+            }
+            Msg::NothingSelectedToPolish => "No hay nada seleccionado para pulir",
+            Msg::ClipboardEmpty => "El portapapeles está vacío",
+
+            Msg::InvalidShortcutName => "Nombre de atajo no válido",
+            Msg::InvalidShortcut => "Atajo no válido",
+            Msg::ShortcutsConfigFailed => "No se pudo leer la configuración de atajos",
+            Msg::InvalidBackendName => "Nombre de servidor no válido",
+            Msg::StoreSecretFailed => "No se pudo guardar el secreto",
+            Msg::HistoryItemNotFound => "No se encontró el elemento del historial",
+            Msg::ReadHistoryFailed => "No se pudo leer el historial",
+            Msg::SearchHistoryFailed => "No se pudo buscar en el historial",
+            Msg::DeleteHistoryFailed => "No se pudo borrar el elemento del historial",
+            Msg::NoArchivedRecording => {
+                "No hay ninguna grabación archivada para este elemento"
+            }
+            Msg::UnknownBackend => "Servidor desconocido",
+            Msg::RetranscribeFailed => "No se pudo transcribir la grabación",
+            Msg::SaveTranscriptionFailed => "No se pudo guardar la transcripción",
+            Msg::ReadTranscriptionFailed => "No se pudo volver a leer la transcripción",
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Hands out the token that in-flight transcriptions and polishes watch.
+/// Cancelling swaps in a fresh token so requests started afterwards are not
+/// affected.
+#[derive(Default)]
+pub struct InFlight(Mutex<CancellationToken>);
+
+impl InFlight {
+    pub fn token(&self) -> CancellationToken {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn cancel(&self) {
+        let mut token = self.0.lock().unwrap();
+        token.cancel();
+        *token = CancellationToken::new();
+    }
+}
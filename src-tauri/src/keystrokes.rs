@@ -0,0 +1,204 @@
+use anyhow::{Result, bail};
+use rdev::{EventType, Key, simulate};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, thread::sleep, time::Duration};
+
+/// A key combination such as `Ctrl+Shift+V`, simulated by holding down the
+/// modifiers in order, tapping the key and releasing the modifiers in reverse
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Chord {
+    modifiers: Vec<Key>,
+    key: Key,
+}
+
+impl Chord {
+    /// The platform's paste shortcut: `Cmd+V` on macOS, `Ctrl+V` elsewhere
+    pub fn paste() -> Self {
+        Self::command(Key::KeyV)
+    }
+
+    /// The platform's copy shortcut: `Cmd+C` on macOS, `Ctrl+C` elsewhere
+    pub fn copy() -> Self {
+        Self::command(Key::KeyC)
+    }
+
+    /// The platform's undo shortcut: `Cmd+Z` on macOS, `Ctrl+Z` elsewhere
+    pub fn undo() -> Self {
+        Self::command(Key::KeyZ)
+    }
+
+    fn command(key: Key) -> Self {
+        let modifier = if cfg!(target_os = "macos") {
+            Key::MetaLeft
+        } else {
+            Key::ControlLeft
+        };
+
+        Self {
+            modifiers: vec![modifier],
+            key,
+        }
+    }
+
+    /// This should only be called on the main thread
+    pub fn simulate(&self) -> Result<()> {
+        let delay = Duration::from_millis(20);
+
+        for &modifier in &self.modifiers {
+            simulate(&EventType::KeyPress(modifier))?;
+            sleep(delay);
+        }
+        simulate(&EventType::KeyPress(self.key))?;
+        sleep(delay);
+        simulate(&EventType::KeyRelease(self.key))?;
+        sleep(delay);
+        for &modifier in self.modifiers.iter().rev() {
+            simulate(&EventType::KeyRelease(modifier))?;
+            sleep(delay);
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Chord {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+        let Some(key) = parts.pop() else {
+            bail!("Empty key combination");
+        };
+
+        let modifiers = parts
+            .into_iter()
+            .map(|part| match part.to_lowercase().as_str() {
+                "ctrl" | "control" => Ok(Key::ControlLeft),
+                "shift" => Ok(Key::ShiftLeft),
+                "alt" | "option" => Ok(Key::Alt),
+                "cmd" | "command" | "meta" | "super" => Ok(Key::MetaLeft),
+                _ => bail!("Unknown modifier '{part}' in '{s}'"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let key = match key.to_lowercase().as_str() {
+            "a" => Key::KeyA,
+            "b" => Key::KeyB,
+            "c" => Key::KeyC,
+            "d" => Key::KeyD,
+            "e" => Key::KeyE,
+            "f" => Key::KeyF,
+            "g" => Key::KeyG,
+            "h" => Key::KeyH,
+            "i" => Key::KeyI,
+            "j" => Key::KeyJ,
+            "k" => Key::KeyK,
+            "l" => Key::KeyL,
+            "m" => Key::KeyM,
+            "n" => Key::KeyN,
+            "o" => Key::KeyO,
+            "p" => Key::KeyP,
+            "q" => Key::KeyQ,
+            "r" => Key::KeyR,
+            "s" => Key::KeyS,
+            "t" => Key::KeyT,
+            "u" => Key::KeyU,
+            "v" => Key::KeyV,
+            "w" => Key::KeyW,
+            "x" => Key::KeyX,
+            "y" => Key::KeyY,
+            "z" => Key::KeyZ,
+            "insert" => Key::Insert,
+            "enter" | "return" => Key::Return,
+            _ => bail!("Unsupported key '{key}' in '{s}'"),
+        };
+
+        Ok(Self { modifiers, key })
+    }
+}
+
+impl TryFrom<String> for Chord {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Chord> for String {
+    fn from(chord: Chord) -> Self {
+        chord.to_string()
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            let name = match modifier {
+                Key::ControlLeft => "Ctrl",
+                Key::ShiftLeft => "Shift",
+                Key::Alt => "Alt",
+                _ => "Cmd",
+            };
+            write!(f, "{name}+")?;
+        }
+
+        match self.key {
+            Key::Insert => write!(f, "Insert"),
+            Key::Return => write!(f, "Enter"),
+            // `KeyA` -> `A`
+            key => write!(f, "{}", format!("{key:?}").trim_start_matches("Key")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(s: &str) -> Chord {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_modifiers_in_order() {
+        assert_eq!(
+            chord("ctrl+shift+v"),
+            Chord {
+                modifiers: vec![Key::ControlLeft, Key::ShiftLeft],
+                key: Key::KeyV,
+            }
+        );
+        assert_eq!(
+            chord("Option + Command + Return"),
+            Chord {
+                modifiers: vec![Key::Alt, Key::MetaLeft],
+                key: Key::Return,
+            }
+        );
+    }
+
+    #[test]
+    fn displays_the_canonical_spelling() {
+        assert_eq!(
+            chord("control+SHIFT+insert").to_string(),
+            "Ctrl+Shift+Insert"
+        );
+        assert_eq!(chord("super+option+enter").to_string(), "Cmd+Alt+Enter");
+        assert_eq!(chord("v").to_string(), "V");
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_modifiers() {
+        assert!("Hyper+V".parse::<Chord>().is_err());
+        assert!("Ctrl+F5".parse::<Chord>().is_err());
+        assert!("".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn round_trips_through_the_config() {
+        let chord: Chord = serde_json::from_str(r#""shift+insert""#).unwrap();
+        assert_eq!(serde_json::to_string(&chord).unwrap(), r#""Shift+Insert""#);
+    }
+}
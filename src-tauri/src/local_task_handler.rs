@@ -1,12 +1,14 @@
 use crate::{
+    app_state::StateMachine,
     audio_recorder::AudioRecorder,
+    config::{AppConfig, PasteOverride},
+    keystrokes::Chord,
     media_manager::MediaManager,
     notifications::{AppNotifications, Notification},
-    transcribe_icon::{Icon, TranscribeIcon},
 };
 use anyhow::Result;
-use rdev::{EventType, Key, simulate};
-use std::{cell::RefCell, rc::Rc, thread::sleep};
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::{cell::RefCell, rc::Rc, sync::Mutex, thread::sleep, time::Duration};
 use tauri::{AppHandle, Manager};
 use tokio::{
     sync::{
@@ -18,21 +20,81 @@ use tokio::{
 
 /// Tasks that will only be run on a `LocalSet`
 pub enum Task {
-    ToggleRecording(oneshot::Sender<Vec<u8>>),
+    StartRecording(oneshot::Sender<Result<()>>),
+    /// Sends back the recording, or `None` if nothing was recorded
+    StopRecording(oneshot::Sender<Option<Vec<u8>>>),
+    /// Pauses the recording with `true`, resumes it with `false`
+    SetPaused(bool),
     PasteFromClipboard,
+    TypeText {
+        text: String,
+        key_delay: Duration,
+    },
+    /// Presses backspace `chars` times, taking back text that was typed
+    EraseText {
+        chars: usize,
+        key_delay: Duration,
+    },
     UndoText(oneshot::Sender<()>),
+    CopySelection(oneshot::Sender<()>),
     CancelRecording,
 }
 
-/// This should only be called on the main thread
-fn paste_from_clipboard() -> Result<()> {
-    simulate(&EventType::KeyPress(Key::MetaLeft))?;
-    sleep(std::time::Duration::from_millis(20));
-    simulate(&EventType::KeyPress(Key::KeyV))?;
-    sleep(std::time::Duration::from_millis(20));
-    simulate(&EventType::KeyRelease(Key::MetaLeft))?;
-    sleep(std::time::Duration::from_millis(20));
-    simulate(&EventType::KeyRelease(Key::KeyV))?;
+/// Undoes the last edit in the focused window, which removes the text that was
+/// pasted there before it gets replaced
+fn undo_text() -> Result<()> {
+    Chord::undo().simulate()?;
+    // Give the target app a moment to apply the undo before anything else is
+    // typed or pasted
+    sleep(Duration::from_millis(50));
+    Ok(())
+}
+
+/// Pastes with the override for the focused app if there is one, otherwise with
+/// the platform's paste shortcut. This should only be called on the main thread.
+fn paste_from_clipboard(overrides: &[PasteOverride]) -> Result<()> {
+    let app_name = active_win_pos_rs::get_active_window()
+        .map(|window| window.app_name)
+        .unwrap_or_default();
+
+    let chord = overrides
+        .iter()
+        .find(|o| o.app.eq_ignore_ascii_case(&app_name))
+        .map(|o| o.keys.clone())
+        .unwrap_or_else(Chord::paste);
+
+    log::debug!("Pasting into '{app_name}' with {chord}");
+
+    chord.simulate()
+}
+
+/// Types `text` into the focused window one character at a time. Unlike
+/// pasting, this handles any unicode and leaves the clipboard alone.
+fn type_text(text: &str, key_delay: Duration) -> Result<()> {
+    let mut enigo = Enigo::new(&Settings::default())?;
+    let mut buf = [0; 4];
+    for c in text.chars() {
+        match c {
+            // Typing `\n` as text doesn't start a new line in every app
+            '\n' => enigo.key(Key::Return, Direction::Click)?,
+            '\r' => continue,
+            c => enigo.text(c.encode_utf8(&mut buf))?,
+        }
+        if !key_delay.is_zero() {
+            sleep(key_delay);
+        }
+    }
+    Ok(())
+}
+
+fn erase_text(chars: usize, key_delay: Duration) -> Result<()> {
+    let mut enigo = Enigo::new(&Settings::default())?;
+    for _ in 0..chars {
+        enigo.key(Key::Backspace, Direction::Click)?;
+        if !key_delay.is_zero() {
+            sleep(key_delay);
+        }
+    }
     Ok(())
 }
 
@@ -55,45 +117,77 @@ pub fn run_local_task_handler(mut rx: mpsc::Receiver<Task>, app_handle: AppHandl
             let app_handle = app_handle.clone();
             tokio::task::spawn_local(async move {
                 match task {
-                    Task::ToggleRecording(tx_recording) => {
-                        log::info!("ToggleRecording task received through channel");
+                    Task::StartRecording(tx_started) => {
+                        log::info!("StartRecording task received through channel");
 
                         let mut recorder = audio_recorder.borrow_mut();
-                        let mut media_manager = media_manager.borrow_mut();
-
-                        if !recorder.is_recording {
-                            media_manager.pause_spotify();
-                            if let Err(e) = recorder.start_recording(
-                                app_handle.state::<Sender<Task>>().clone(),
-                            ) {
-                                log::error!("Failed to start recording: {}", e);
-                                recorder.reset();
-                                return;
-                            }
-                            _ = tx_recording.send(vec![]);
-                            return;
+                        media_manager.borrow_mut().pause_spotify();
+
+                        let result = recorder
+                            .start_recording(app_handle.state::<Sender<Task>>().clone());
+                        if result.is_err() {
+                            recorder.reset();
                         }
+                        _ = tx_started.send(result);
+                    }
+                    Task::StopRecording(tx_recording) => {
+                        log::info!("StopRecording task received through channel");
 
-                        let Some(recording_bytes) =
-                            recorder.stop_recording_and_get_bytes()
-                        else {
-                            log::error!("Failed to stop recording");
-                            return;
-                        };
-                        media_manager.play_spotify();
+                        let recording_bytes =
+                            audio_recorder.borrow_mut().stop_recording_and_get_bytes();
+                        media_manager.borrow_mut().play_spotify();
 
                         if tx_recording.send(recording_bytes).is_err() {
                             log::error!("Failed to send recording to channel");
                         }
                     }
-                    Task::PasteFromClipboard => match paste_from_clipboard() {
-                        Ok(()) => log::info!("Pasted from clipboard successfully"),
-                        Err(e) => log::error!("Failed to paste from clipboard: {}", e),
-                    },
-                    Task::UndoText(_tx_undo) => {
-                        unimplemented!(
-                            "UndoText task received through channel (not implemented)"
-                        );
+                    Task::SetPaused(paused) => {
+                        let recorder = audio_recorder.borrow();
+                        let result = if paused {
+                            recorder.pause()
+                        } else {
+                            recorder.resume()
+                        };
+                        if let Err(e) = result {
+                            log::error!("Failed to pause or resume recording: {}", e);
+                        }
+                    }
+                    Task::PasteFromClipboard => {
+                        let overrides = app_handle
+                            .state::<Mutex<AppConfig>>()
+                            .lock()
+                            .unwrap()
+                            .paste_overrides
+                            .clone();
+                        match paste_from_clipboard(&overrides) {
+                            Ok(()) => log::info!("Pasted from clipboard successfully"),
+                            Err(e) => {
+                                log::error!("Failed to paste from clipboard: {}", e)
+                            }
+                        }
+                    }
+                    Task::TypeText { text, key_delay } => {
+                        if let Err(e) = type_text(&text, key_delay) {
+                            log::error!("Failed to type text: {}", e);
+                        }
+                    }
+                    Task::EraseText { chars, key_delay } => {
+                        if let Err(e) = erase_text(chars, key_delay) {
+                            log::error!("Failed to erase text: {}", e);
+                        }
+                    }
+                    Task::UndoText(tx_undo) => {
+                        match undo_text() {
+                            Ok(()) => log::info!("Undid previously pasted text"),
+                            Err(e) => log::error!("Failed to undo text: {}", e),
+                        }
+                        _ = tx_undo.send(());
+                    }
+                    Task::CopySelection(tx_copy) => {
+                        if let Err(e) = Chord::copy().simulate() {
+                            log::error!("Failed to copy selection: {}", e);
+                        }
+                        _ = tx_copy.send(());
                     }
                     Task::CancelRecording => {
                         let mut recorder = audio_recorder.borrow_mut();
@@ -101,12 +195,11 @@ pub fn run_local_task_handler(mut rx: mpsc::Receiver<Task>, app_handle: AppHandl
                             log::error!("Failed to stop recording");
                             return;
                         }
-                        let icon = app_handle.state::<TranscribeIcon>();
-                        icon.change_icon(Icon::Default);
+                        app_handle.state::<StateMachine>().finish(&app_handle);
                         AppNotifications::new(&app_handle)
                             .notify(Notification::CancelledSilence);
 
-                        log::info!("Recording cancelled and icon changed to default");
+                        log::info!("Recording cancelled");
                     }
                 }
             });
@@ -1,35 +1,106 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod app_state;
+mod audio_archive;
 mod audio_recorder;
+mod circuit_breaker;
+mod clipboard_snapshot;
 mod commands;
+mod config;
 mod constants;
+mod events;
+mod history;
+mod i18n;
+mod in_flight;
+mod keystrokes;
 mod local_task_handler;
 mod media_manager;
 mod notifications;
+mod output_sink;
+mod recent;
+mod secrets;
 mod shortcuts;
+mod sound_cues;
 mod transcribe_client;
 mod transcribe_icon;
+mod vocabulary;
 
 use anyhow::Context;
+use app_state::{AppState, StateMachine};
+use chrono::Local;
+use clipboard_snapshot::ClipboardSnapshot;
 use colored::*;
+use config::{
+    AppConfig, PolishPreset, TranslationConfig, TranslationMethod,
+    get_or_create_app_config, save_do_not_disturb,
+};
+use events::{ErrorCode, PipelineEvent};
+use history::{History, HistoryKind, NewHistoryItem};
+use i18n::{Msg, t};
+use in_flight::InFlight;
 use local_task_handler::{Task, run_local_task_handler};
 use notifications::{AppNotifications, Notification};
+use output_sink::OutputSink;
+use recent::Recent;
 use shortcuts::{ShortcutsConfig, get_or_create_shortcuts_config};
-use std::sync::Mutex;
+use sound_cues::SoundCues;
+use std::{sync::Mutex, time::Duration};
 use tauri::{
-    AppHandle, Manager,
+    AppHandle, Manager, Wry,
     async_runtime::spawn,
-    menu::{MenuBuilder, MenuItem},
+    menu::{CheckMenuItem, MenuBuilder, MenuItem, SubmenuBuilder},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
 };
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
-use tauri_plugin_notification::NotificationExt;
 use tokio::sync::{mpsc, oneshot};
-use transcribe_client::TranscribeClient;
-use transcribe_icon::{Icon, TranscribeIcon};
+use transcribe_client::{TranscribeClient, Transcription};
+use transcribe_icon::TranscribeIcon;
+use vocabulary::VocabularyStream;
+
+/// What was last dictated, for pasting it again or retrying it
+#[derive(Default)]
+struct LastDictation {
+    /// Kept even when transcribing it failed
+    recording: Option<Vec<u8>>,
+    translation: Option<TranslationConfig>,
+    transcription: Option<Transcription>,
+    /// Whether the transcription went into the focused window as a single
+    /// paste, which is the last edit there, so one undo takes it out again
+    pasted: bool,
+}
+
+/// Records whether the last thing put into the focused window was the last
+/// transcription, pasted in one go
+fn set_last_pasted(app_handle: &AppHandle, pasted: bool) {
+    app_handle
+        .state::<Mutex<LastDictation>>()
+        .lock()
+        .unwrap()
+        .pasted = pasted;
+}
+
+/// Settings captured when a recording starts, so stopping it with another
+/// shortcut or changing the config meanwhile doesn't change how it's handled
+#[derive(Default)]
+struct CurrentRecording {
+    translation: Option<TranslationConfig>,
+}
+
+/// Where `cleanse_clipboard` takes its text from and puts the result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolishTarget {
+    /// Polish the clipboard and leave the result there
+    Clipboard,
+    /// Polish the last transcript and paste the result over it, undoing the
+    /// paste of the transcript first
+    LastPaste,
+    /// Copy the selection in the focused window, polish it and paste the
+    /// result over it
+    Selection,
+}
 
-struct IsCleansing(bool);
+struct BackendStatusItem(MenuItem<Wry>);
 
 fn main() {
     tauri::Builder::default()
@@ -46,6 +117,10 @@ fn main() {
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
+            let app_config = get_or_create_app_config()?;
+            let language = i18n::init(app_config.language);
+            log::info!("Using language {:?}", language);
+
             #[cfg(desktop)]
             {
                 let shortcuts_config = get_or_create_shortcuts_config()?;
@@ -64,14 +139,83 @@ fn main() {
                                 && event.state() == ShortcutState::Pressed
                             {
                                 log::info!("F19 shortcut triggered - Start/Stop Recording");
-                                toggle_recording(app.clone(), true);
+                                toggle_recording(app.clone(), true, None);
                             }
                             // Check if the shortcut matches F20
                             else if shortcut == &shortcuts_config.cleanse_clipboard
                                 && event.state() == ShortcutState::Pressed
                             {
                                 log::info!("F20 shortcut triggered - Polish Clipboard");
-                                cleanse_clipboard(app.clone(), false);
+                                cleanse_clipboard(
+                                    app.clone(),
+                                    PolishTarget::Clipboard,
+                                    None,
+                                );
+                            } else if shortcut == &shortcuts_config.polish_selection
+                                && event.state() == ShortcutState::Pressed
+                            {
+                                log::info!("Polish selection shortcut triggered");
+                                cleanse_clipboard(
+                                    app.clone(),
+                                    PolishTarget::Selection,
+                                    None,
+                                );
+                            } else if shortcut == &shortcuts_config.repaste_last
+                                && event.state() == ShortcutState::Pressed
+                            {
+                                log::info!("Re-paste last shortcut triggered");
+                                repaste_last(app.clone(), true);
+                            } else if shortcut == &shortcuts_config.retry_last
+                                && event.state() == ShortcutState::Pressed
+                            {
+                                log::info!("Retry last shortcut triggered");
+                                retry_last(app.clone(), true);
+                            } else if shortcut == &shortcuts_config.polish_last_paste
+                                && event.state() == ShortcutState::Pressed
+                            {
+                                log::info!("Polish last paste shortcut triggered");
+                                cleanse_clipboard(
+                                    app.clone(),
+                                    PolishTarget::LastPaste,
+                                    None,
+                                );
+                            } else if shortcut == &shortcuts_config.cancel
+                                && event.state() == ShortcutState::Pressed
+                            {
+                                log::info!("Cancel shortcut triggered");
+                                cancel_in_flight(app);
+                            } else if event.state() == ShortcutState::Pressed {
+                                let app_config = app.state::<Mutex<AppConfig>>();
+                                let app_config = app_config.lock().unwrap();
+                                if let Some(preset) = app_config
+                                    .presets
+                                    .iter()
+                                    .find(|p| p.shortcut.as_ref() == Some(shortcut))
+                                {
+                                    log::info!(
+                                        "Preset shortcut triggered - {}",
+                                        preset.name
+                                    );
+                                    cleanse_clipboard(
+                                        app.clone(),
+                                        PolishTarget::Clipboard,
+                                        Some(preset.clone()),
+                                    );
+                                } else if let Some(translation) = app_config
+                                    .translations
+                                    .iter()
+                                    .find(|t| &t.shortcut == shortcut)
+                                {
+                                    log::info!(
+                                        "Translation shortcut triggered - {}",
+                                        translation.target_language
+                                    );
+                                    toggle_recording(
+                                        app.clone(),
+                                        true,
+                                        Some(translation.clone()),
+                                    );
+                                }
                             }
                         })
                         .build(),
@@ -80,9 +224,65 @@ fn main() {
                     shortcuts_config.toggle_recording,
                     shortcuts_config.cleanse_clipboard,
                 ])?;
+                // Another app may hold one of these already, which shouldn't keep
+                // dictation from starting
+                let optional_shortcuts = [
+                    ("cancel", shortcuts_config.cancel),
+                    ("polish selection", shortcuts_config.polish_selection),
+                    ("repaste last", shortcuts_config.repaste_last),
+                    ("retry last", shortcuts_config.retry_last),
+                    ("polish last paste", shortcuts_config.polish_last_paste),
+                ];
+                for (name, shortcut) in optional_shortcuts {
+                    if let Err(e) = app.global_shortcut().register(shortcut) {
+                        log::error!("Failed to register shortcut for '{name}': {e}");
+                    }
+                }
+                for preset in &app_config.presets {
+                    let Some(shortcut) = preset.shortcut else {
+                        continue;
+                    };
+                    if let Err(e) = app.global_shortcut().register(shortcut) {
+                        log::error!(
+                            "Failed to register shortcut for '{}': {e}",
+                            preset.name
+                        );
+                    }
+                }
+                for translation in &app_config.translations {
+                    if let Err(e) = app.global_shortcut().register(translation.shortcut) {
+                        log::error!(
+                            "Failed to register shortcut for '{}': {e}",
+                            translation.target_language
+                        );
+                    }
+                }
                 log::info!("Registered global shortcuts");
             }
 
+            let presets_menu = app_config
+                .presets
+                .iter()
+                .fold(
+                    SubmenuBuilder::new(app, t(Msg::PolishWith)),
+                    |menu, preset| {
+                        menu.text(format!("preset:{}", preset.name), &preset.name)
+                    },
+                )
+                .enabled(!app_config.presets.is_empty())
+                .build()?;
+
+            let history = History::open()?;
+            if let Err(e) = audio_archive::prune(&app_config.archive) {
+                log::error!("Failed to prune archived recordings: {e}");
+            }
+            let recent = Recent::new(app, app_config.recent_count)?;
+            let icon_config = app_config.icons.clone();
+            let do_not_disturb = app_config.notifications.do_not_disturb;
+            let sound_cues = SoundCues::new(app_config.sounds.clone());
+
+            app.manage(Mutex::new(app_config));
+
             // Channel for sending tasks to the local task handler
             let (localtask_tx, localtask_rx) = mpsc::channel::<Task>(1);
 
@@ -93,33 +293,81 @@ fn main() {
                 run_local_task_handler(localtask_rx, app_handle);
             });
 
-            app.notification()
-                .builder()
-                .title("Tauri")
-                .body("Tauri is awesome")
-                .show()
-                .unwrap();
+            let backend_status = MenuItem::with_id(
+                app,
+                "backend_status",
+                t(Msg::BackendChecking),
+                false,
+                None::<&str>,
+            )?;
+
+            let toggle_item = MenuItem::with_id(
+                app,
+                "toggle_recording",
+                t(Msg::StartRecording),
+                true,
+                None::<&str>,
+            )?;
+            let pause_item = MenuItem::with_id(
+                app,
+                "pause",
+                t(Msg::PauseRecording),
+                false,
+                None::<&str>,
+            )?;
+            let cancel_item =
+                MenuItem::with_id(app, "cancel", t(Msg::Cancel), false, None::<&str>)?;
 
             let menu = MenuBuilder::new(app)
+                .item(&toggle_item)
+                .item(&pause_item)
                 .item(&MenuItem::with_id(
                     app,
-                    "toggle_recording",
-                    "Toggle Recording",
+                    "cleanse",
+                    t(Msg::PolishClipboard),
                     true,
                     None::<&str>,
                 )?)
                 .item(&MenuItem::with_id(
                     app,
-                    "cleanse",
-                    "Polish clipboard",
+                    "polish_selection",
+                    t(Msg::PolishSelection),
+                    true,
+                    None::<&str>,
+                )?)
+                .item(&presets_menu)
+                .item(&cancel_item)
+                .item(&MenuItem::with_id(
+                    app,
+                    "copy_last",
+                    t(Msg::CopyLastTranscript),
+                    true,
+                    None::<&str>,
+                )?)
+                .item(&MenuItem::with_id(
+                    app,
+                    "retry_last",
+                    t(Msg::RetryLastRecording),
+                    true,
+                    None::<&str>,
+                )?)
+                .item(recent.submenu())
+                .separator()
+                .item(&backend_status)
+                .separator()
+                .item(&CheckMenuItem::with_id(
+                    app,
+                    "do_not_disturb",
+                    t(Msg::DoNotDisturb),
                     true,
+                    do_not_disturb,
                     None::<&str>,
                 )?)
                 .separator()
                 .item(&MenuItem::with_id(
                     app,
                     "open_window",
-                    "Open Window",
+                    t(Msg::OpenWindow),
                     true,
                     None::<&str>,
                 )?)
@@ -127,7 +375,7 @@ fn main() {
                 .item(&MenuItem::with_id(
                     app,
                     "quit",
-                    "Quit app",
+                    t(Msg::QuitApp),
                     true,
                     None::<&str>,
                 )?)
@@ -140,15 +388,57 @@ fn main() {
                 .build(app)?;
 
             let transcribe_client = TranscribeClient::new();
+            let transcribe_icon = TranscribeIcon::new(tray_icon, &icon_config)?;
 
             app.manage(localtask_tx)
                 .then(|| app.manage(transcribe_client))
-                .and_then(|_| app.manage(TranscribeIcon::new(tray_icon)).into())
-                .and_then(|_| app.manage(Mutex::new(IsCleansing(false))).into())
+                .and_then(|_| app.manage(transcribe_icon).into())
+                .and_then(|_| {
+                    app.manage(StateMachine::new(toggle_item, pause_item, cancel_item))
+                        .into()
+                })
+                .and_then(|_| app.manage(BackendStatusItem(backend_status)).into())
+                .and_then(|_| app.manage(InFlight::default()).into())
+                .and_then(|_| app.manage(history).into())
+                .and_then(|_| app.manage(recent).into())
+                .and_then(|_| app.manage(sound_cues).into())
+                .and_then(|_| app.manage(Mutex::new(LastDictation::default())).into())
+                .and_then(|_| app.manage(Mutex::new(CurrentRecording::default())).into())
                 .context("Failed to manage app state")?;
 
             log::info!("Successfully managed app state");
 
+            update_backend_status(app.handle());
+
+            // Keeps the recording timer next to the tray icon running
+            let app_handle = app.handle().clone();
+            spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    app_handle.state::<StateMachine>().tick(&app_handle);
+                }
+            });
+
+            // Give backends with an open circuit breaker a chance to recover
+            // without waiting for the next recording
+            let app_handle = app.handle().clone();
+            spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    let backends = app_handle
+                        .state::<Mutex<AppConfig>>()
+                        .lock()
+                        .unwrap()
+                        .transcription_backends
+                        .clone();
+                    app_handle
+                        .state::<TranscribeClient>()
+                        .probe_backends(&backends)
+                        .await;
+                    update_backend_status(&app_handle);
+                }
+            });
+
             Ok(())
         })
         .on_tray_icon_event(|app_handle, event| match event {
@@ -157,7 +447,7 @@ fn main() {
                 button_state: MouseButtonState::Down,
                 ..
             } => {
-                toggle_recording(app_handle.clone(), false);
+                toggle_recording(app_handle.clone(), false, None);
             }
             TrayIconEvent::Click {
                 button: MouseButton::Right,
@@ -187,10 +477,42 @@ fn main() {
                     app_handle.exit(0);
                 }
                 "toggle_recording" => {
-                    toggle_recording(app_handle.clone(), false);
+                    // Reads "Cancel" while waiting on a backend
+                    if app_handle.state::<StateMachine>().current().is_busy() {
+                        cancel_in_flight(app_handle);
+                    } else {
+                        toggle_recording(app_handle.clone(), false, None);
+                    }
+                }
+                "pause" => {
+                    toggle_pause(app_handle.clone());
                 }
                 "cleanse" => {
-                    cleanse_clipboard(app_handle.clone(), false);
+                    cleanse_clipboard(app_handle.clone(), PolishTarget::Clipboard, None);
+                }
+                "polish_selection" => {
+                    cleanse_clipboard(app_handle.clone(), PolishTarget::Selection, None);
+                }
+                "cancel" => {
+                    cancel_in_flight(app_handle);
+                }
+                // Like everything else in the tray, there's no window to paste into
+                "copy_last" => {
+                    repaste_last(app_handle.clone(), false);
+                }
+                "retry_last" => {
+                    retry_last(app_handle.clone(), false);
+                }
+                "do_not_disturb" => {
+                    let config = app_handle.state::<Mutex<AppConfig>>();
+                    let mut config = config.lock().unwrap();
+                    let notifications = &mut config.notifications;
+                    notifications.do_not_disturb = !notifications.do_not_disturb;
+                    log::info!("Do not disturb: {}", notifications.do_not_disturb);
+
+                    if let Err(e) = save_do_not_disturb(notifications.do_not_disturb) {
+                        log::error!("Failed to save do not disturb: {}", e);
+                    }
                 }
                 "open_window" => {
                     if let Some(window) = app_handle.get_webview_window("main") {
@@ -201,159 +523,886 @@ fn main() {
                         log::error!("Failed to get webview window");
                     }
                 }
+                "clear_recent" => {
+                    app_handle.state::<Recent>().clear(app_handle);
+                }
+                id if id.starts_with("recent:") => {
+                    let text = id
+                        .trim_start_matches("recent:")
+                        .parse()
+                        .ok()
+                        .and_then(|index| app_handle.state::<Recent>().get(index));
+                    match text {
+                        Some(text) => {
+                            if let Err(e) = app_handle.clipboard().write_text(text) {
+                                log::error!("Failed to write text to clipboard: {}", e);
+                            }
+                        }
+                        None => log::warn!("Unknown recent item: {}", id),
+                    }
+                }
+                id if id.starts_with("preset:") => {
+                    let name = id.trim_start_matches("preset:");
+                    let preset = app_handle
+                        .state::<Mutex<AppConfig>>()
+                        .lock()
+                        .unwrap()
+                        .presets
+                        .iter()
+                        .find(|p| p.name == name)
+                        .cloned();
+                    match preset {
+                        Some(preset) => cleanse_clipboard(
+                            app_handle.clone(),
+                            PolishTarget::Clipboard,
+                            Some(preset),
+                        ),
+                        None => log::warn!("Unknown preset: {}", name),
+                    }
+                }
                 id => {
                     log::warn!("Unknown menu event: {}", id);
                 }
             }
         })
-        .invoke_handler(tauri::generate_handler![commands::assign_shortcut])
+        .invoke_handler(tauri::generate_handler![
+            commands::assign_shortcut,
+            commands::set_backend_secret,
+            commands::search_history,
+            commands::get_history_item,
+            commands::delete_history_item,
+            commands::retranscribe,
+            commands::get_app_state,
+            commands::get_language
+        ])
         .plugin(tauri_plugin_clipboard_manager::init())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
-pub fn toggle_recording(app_handle: AppHandle, paste_from_clipboard: bool) {
+/// Shows the first healthy backend of the chain in the tray menu
+fn update_backend_status(app_handle: &AppHandle) {
+    let backends = app_handle
+        .state::<Mutex<AppConfig>>()
+        .lock()
+        .unwrap()
+        .transcription_backends
+        .clone();
+
+    let healthy = app_handle
+        .state::<TranscribeClient>()
+        .healthy_backend(&backends);
+    app_handle
+        .state::<StateMachine>()
+        .set_offline(app_handle, healthy.is_none());
+
+    let status = match healthy {
+        Some(name) => format!("{}: {name}", t(Msg::Backend)),
+        None => t(Msg::BackendNone).into(),
+    };
+
+    if let Err(e) = app_handle.state::<BackendStatusItem>().0.set_text(status) {
+        log::error!("Failed to update backend status: {}", e);
+    }
+}
+
+/// Discards the recording, or aborts the transcription or polish in flight.
+/// Nothing it produces reaches the clipboard or gets pasted.
+pub fn cancel_in_flight(app_handle: &AppHandle) {
+    if !app_handle.state::<StateMachine>().current().is_recording() {
+        log::info!("Cancelling in-flight requests");
+        app_handle.state::<InFlight>().cancel();
+        return;
+    }
+
+    log::info!("Discarding recording");
+    let app_handle = app_handle.clone();
     spawn(async move {
+        let (tx_recording, rx_recording) = oneshot::channel::<Option<Vec<u8>>>();
+        if let Err(e) = app_handle
+            .state::<mpsc::Sender<Task>>()
+            .send(Task::StopRecording(tx_recording))
+            .await
+        {
+            log::error!("Failed to send 'StopRecording' task to channel: {}", e);
+        }
+        _ = rx_recording.await;
+        app_handle.state::<StateMachine>().finish(&app_handle);
+    });
+}
+
+/// Starts a recording, or stops and transcribes it. `translation` is only read
+/// when starting.
+pub fn toggle_recording(
+    app_handle: AppHandle,
+    paste_from_clipboard: bool,
+    translation: Option<TranslationConfig>,
+) {
+    spawn(async move {
+        let state = app_handle.state::<StateMachine>();
         let tx_task = app_handle.state::<mpsc::Sender<Task>>();
-        let (tx_recording, rx_recording) = oneshot::channel::<Vec<u8>>();
 
-        if let Err(e) = tx_task.send(Task::ToggleRecording(tx_recording)).await {
-            log::error!("Failed to send 'ToggleRecording' task to channel: {}", e);
-            return;
+        let recording = state.current().is_recording();
+        let to = if recording {
+            AppState::Transcribing
+        } else {
+            AppState::Recording
         };
+        if state.transition(&app_handle, to).is_err() {
+            return;
+        }
 
-        let transcribe_icon = app_handle.state::<TranscribeIcon>();
+        if !recording {
+            app_handle
+                .state::<Mutex<CurrentRecording>>()
+                .lock()
+                .unwrap()
+                .translation = translation;
 
-        let recording_bytes = match rx_recording.await {
-            Ok(bytes) => {
-                if bytes.is_empty() {
+            let (tx_started, rx_started) = oneshot::channel::<anyhow::Result<()>>();
+            if let Err(e) = tx_task.send(Task::StartRecording(tx_started)).await {
+                log::error!("Failed to send 'StartRecording' task to channel: {}", e);
+                state.fail(&app_handle, ErrorCode::RecordingFailed, e.to_string());
+                return;
+            }
+            match rx_started.await {
+                Ok(Ok(())) => {
                     log::info!("Starting recording");
-                    transcribe_icon.change_icon(Icon::Recording);
-                    return;
+                    PipelineEvent::RecordingStarted.emit(&app_handle);
+                }
+                Ok(Err(e)) => {
+                    log::error!("Failed to start recording: {}", e);
+                    state.fail(&app_handle, ErrorCode::RecordingFailed, e.to_string());
+                }
+                Err(e) => {
+                    log::error!("Failed to receive 'StartRecording' result: {}", e);
+                    state.fail(&app_handle, ErrorCode::RecordingFailed, e.to_string());
                 }
-                bytes
             }
-            Err(e) => {
-                log::error!(
-                    "Failed to receive 'ToggleRecording' task from channel: {}",
-                    e
+            return;
+        }
+
+        let (tx_recording, rx_recording) = oneshot::channel::<Option<Vec<u8>>>();
+        if let Err(e) = tx_task.send(Task::StopRecording(tx_recording)).await {
+            log::error!("Failed to send 'StopRecording' task to channel: {}", e);
+            state.fail(&app_handle, ErrorCode::RecordingFailed, e.to_string());
+            return;
+        }
+
+        let recording_bytes = match rx_recording.await {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => {
+                log::error!("Failed to stop recording");
+                state.fail(
+                    &app_handle,
+                    ErrorCode::RecordingFailed,
+                    t(Msg::NothingRecorded),
                 );
-                transcribe_icon.change_icon(Icon::Default);
+                return;
+            }
+            Err(e) => {
+                log::error!("Failed to receive recording from channel: {}", e);
+                state.fail(&app_handle, ErrorCode::RecordingFailed, e.to_string());
                 return;
             }
         };
 
-        transcribe_icon.change_icon(Icon::Transcribing);
+        PipelineEvent::RecordingStopped {
+            duration_ms: audio_recorder::wav_duration(&recording_bytes)
+                .map(|d| d.as_millis() as u64),
+        }
+        .emit(&app_handle);
 
-        let transcribe_client = app_handle.state::<TranscribeClient>();
-        let result = transcribe_client.fetch_transcription(recording_bytes).await;
+        // Whatever shortcut stopped it, the recording is translated if the one
+        // that started it asked for that
+        let translation = app_handle
+            .state::<Mutex<CurrentRecording>>()
+            .lock()
+            .unwrap()
+            .translation
+            .take();
 
-        transcribe_icon.change_icon(Icon::Default);
+        transcribe_recording(
+            app_handle.clone(),
+            recording_bytes,
+            paste_from_clipboard,
+            translation,
+        )
+        .await;
+    });
+}
 
-        let Ok(text) = result else {
-            log::error!("Failed to fetch transcription from API");
-            AppNotifications::new(&app_handle).notify(Notification::ApiError);
-            return;
+/// Pauses the recording, or resumes it if it is paused
+pub fn toggle_pause(app_handle: AppHandle) {
+    spawn(async move {
+        let state = app_handle.state::<StateMachine>();
+        let paused = match state.current() {
+            AppState::Recording => true,
+            AppState::Paused => false,
+            current => {
+                log::warn!("Nothing to pause while {current:?}");
+                return;
+            }
         };
 
-        log::info!("Transcription text: {}", text.yellow());
-
-        if let Err(e) = app_handle.clipboard().write_text(text) {
-            log::error!("Failed to write text to clipboard: {}", e);
+        let to = if paused {
+            AppState::Paused
+        } else {
+            AppState::Recording
+        };
+        if state.transition(&app_handle, to).is_err() {
             return;
         }
 
-        if !paste_from_clipboard {
-            AppNotifications::new(&app_handle).notify(Notification::TranscribeSuccess);
+        if let Err(e) = app_handle
+            .state::<mpsc::Sender<Task>>()
+            .send(Task::SetPaused(paused))
+            .await
+        {
+            log::error!("Failed to send 'SetPaused' task to channel: {}", e);
+        }
+    });
+}
+
+/// Pastes the last transcript again, or copies it when `paste_from_clipboard`
+/// is false
+pub fn repaste_last(app_handle: AppHandle, paste_from_clipboard: bool) {
+    spawn(async move {
+        let transcription = app_handle
+            .state::<Mutex<LastDictation>>()
+            .lock()
+            .unwrap()
+            .transcription
+            .clone();
+
+        let Some(transcription) = transcription else {
+            log::warn!("No transcript to paste again");
+            AppNotifications::new(&app_handle).notify(Notification::NothingToRepeat);
+            PipelineEvent::error(ErrorCode::NothingToRepeat, t(Msg::NoTranscriptToPaste))
+                .emit(&app_handle);
+            return;
+        };
+
+        let state = app_handle.state::<StateMachine>();
+        if state.transition(&app_handle, AppState::Pasting).is_err() {
             return;
         }
 
-        if let Err(e) = tx_task.send(Task::PasteFromClipboard).await {
-            log::error!("Failed to send 'PasteFromClipboard' task to channel: {}", e);
-        } else {
-            log::info!("Sent PasteFromClipboard task to channel");
+        let output = app_handle
+            .state::<Mutex<AppConfig>>()
+            .lock()
+            .unwrap()
+            .output
+            .clone();
+
+        match OutputSink::Clipboard
+            .deliver(&app_handle, &transcription, &output, paste_from_clipboard)
+            .await
+        {
+            Ok(()) => {
+                // Only copying leaves the window as it was
+                if paste_from_clipboard {
+                    set_last_pasted(&app_handle, true);
+                }
+                state.finish(&app_handle);
+            }
+            Err(e) => {
+                log::error!("Failed to paste last transcript: {}", e);
+                state.fail(&app_handle, ErrorCode::DeliveryFailed, e.to_string());
+            }
         }
-        log::info!("exiting toggle recording function");
     });
 }
 
-pub fn cleanse_clipboard(app_handle: AppHandle, paste_from_clipboard: bool) {
+/// Transcribes the last recording again, e.g. after the backend failed
+pub fn retry_last(app_handle: AppHandle, paste_from_clipboard: bool) {
     spawn(async move {
-        let Ok(clipboard_text) = app_handle.clipboard().read_text() else {
-            log::error!("Failed to read from clipboard");
+        let last = app_handle.state::<Mutex<LastDictation>>();
+        let last = last.lock().unwrap();
+        let recording = last.recording.clone();
+        let translation = last.translation.clone();
+        drop(last);
+
+        let Some(recording) = recording else {
+            log::warn!("No recording to retry");
+            AppNotifications::new(&app_handle).notify(Notification::NothingToRepeat);
+            PipelineEvent::error(ErrorCode::NothingToRepeat, t(Msg::NoRecordingToRetry))
+                .emit(&app_handle);
             return;
         };
 
-        let notifs = app_handle.notification();
+        let state = app_handle.state::<StateMachine>();
+        if state
+            .transition(&app_handle, AppState::Transcribing)
+            .is_err()
+        {
+            return;
+        }
 
-        if clipboard_text.is_empty() {
-            _ = notifs
-                .builder()
-                .title("Empty clipboard")
-                .body("We couldn't find any text in your clipboard to polish")
-                .show();
+        log::info!("Retrying last recording");
+        transcribe_recording(
+            app_handle.clone(),
+            recording,
+            paste_from_clipboard,
+            translation,
+        )
+        .await;
+    });
+}
+
+/// Sends a finished recording down the backend chain and delivers the result.
+/// Expects the state machine to be in `Transcribing` already.
+async fn transcribe_recording(
+    app_handle: AppHandle,
+    recording_bytes: Vec<u8>,
+    paste_from_clipboard: bool,
+    translation: Option<TranslationConfig>,
+) {
+    let state = app_handle.state::<StateMachine>();
+    PipelineEvent::TranscriptionStarted.emit(&app_handle);
+
+    // Kept before transcribing so a failed attempt can be retried
+    let last = app_handle.state::<Mutex<LastDictation>>();
+    let mut last = last.lock().unwrap();
+    last.recording = Some(recording_bytes.clone());
+    last.translation = translation.clone();
+    drop(last);
+
+    let config = app_handle
+        .state::<Mutex<AppConfig>>()
+        .lock()
+        .unwrap()
+        .clone();
+
+    let translate_on_backend = translation
+        .as_ref()
+        .is_some_and(|t| t.method == TranslationMethod::Backend);
+    let sinks = translation
+        .as_ref()
+        .map(|t| t.output.clone())
+        .filter(|sinks| !sinks.is_empty())
+        .unwrap_or_else(|| config.output.sinks.clone());
+    let target_language = translation.as_ref().map(|t| t.target_language.clone());
+
+    let duration = audio_recorder::wav_duration(&recording_bytes);
+    let archived_recording = config.archive.enabled.then(|| recording_bytes.clone());
+    let started_at = Local::now();
+
+    let cancel = app_handle.state::<InFlight>().token();
+
+    let transcribe_client = app_handle.state::<TranscribeClient>();
+    let result = cancel
+        .run_until_cancelled(transcribe_client.transcribe(
+            &config.transcription_backends,
+            recording_bytes,
+            &config.vocabulary,
+            None,
+            translate_on_backend,
+        ))
+        .await;
+
+    update_backend_status(&app_handle);
+
+    let Some(result) = result else {
+        log::info!("Transcription cancelled");
+        state.finish(&app_handle);
+        return;
+    };
+
+    let mut transcription = match result {
+        Ok(transcription) => transcription,
+        Err(e) => {
+            log::error!("Failed to fetch transcription from API: {}", e);
+            state.fail(&app_handle, ErrorCode::TranscriptionFailed, e.to_string());
+            AppNotifications::new(&app_handle).notify(Notification::TranscriptionError);
             return;
         }
+    };
+
+    if let Some(translation) =
+        translation.filter(|t| t.method == TranslationMethod::Polishing)
+    {
+        _ = state.transition(&app_handle, AppState::Polishing);
 
-        let is_cleansing_m = app_handle.state::<Mutex<IsCleansing>>();
-        let mut is_cleansing = is_cleansing_m.lock().unwrap();
-        if is_cleansing.0 {
-            log::warn!("Already cleansing. Skipping.");
+        let result = cancel
+            .run_until_cancelled(transcribe_client.translate(
+                &config.polishing,
+                &transcription.text,
+                &translation.target_language,
+            ))
+            .await;
+
+        let Some(result) = result else {
+            log::info!("Translation cancelled");
+            state.finish(&app_handle);
             return;
+        };
+
+        let translated = match result {
+            Ok(translated) => translated,
+            Err(e) => {
+                log::error!("Failed to translate transcription: {}", e);
+                state.fail(&app_handle, ErrorCode::TranslationFailed, e.to_string());
+                AppNotifications::new(&app_handle)
+                    .notify(Notification::TranscriptionError);
+                return;
+            }
+        };
+
+        transcription.translation = Some(translated);
+    }
+
+    transcription.text =
+        vocabulary::apply_vocabulary(&transcription.text, &config.vocabulary);
+    transcription.translation = transcription
+        .translation
+        .map(|text| vocabulary::apply_vocabulary(&text, &config.vocabulary));
+
+    log::info!("Transcription text: {}", transcription.text.yellow());
+    if let Some(translated) = &transcription.translation {
+        log::info!("Translated text: {}", translated.yellow());
+    }
+
+    // Cancelled between the response arriving and getting here
+    if cancel.is_cancelled() {
+        log::info!("Transcription cancelled, discarding result");
+        state.finish(&app_handle);
+        return;
+    }
+
+    PipelineEvent::TranscriptionResult {
+        text: transcription.text.clone(),
+        translation: transcription.translation.clone(),
+        backend: transcription.backend.clone(),
+    }
+    .emit(&app_handle);
+
+    let last = app_handle.state::<Mutex<LastDictation>>();
+    let mut last = last.lock().unwrap();
+    last.transcription = Some(transcription.clone());
+    last.pasted = false;
+    drop(last);
+    app_handle
+        .state::<Recent>()
+        .push(&app_handle, transcription.output());
+
+    let item = NewHistoryItem {
+        kind: if transcription.translation.is_some() {
+            HistoryKind::Translation
+        } else {
+            HistoryKind::Transcription
+        },
+        raw_text: &transcription.text,
+        polished_text: transcription.translation.as_deref(),
+        duration,
+        backend: Some(&transcription.backend),
+        language: target_language
+            .as_deref()
+            .or(transcription.language.as_deref()),
+        sinks: sinks.iter().map(OutputSink::name).collect(),
+        started_at,
+    };
+    match app_handle.state::<History>().record(&item) {
+        Ok(id) => {
+            let archived = archived_recording
+                .map(|recording| audio_archive::save(id, &recording, &config.archive));
+            if let Some(Err(e)) = archived {
+                log::error!("Failed to archive recording: {}", e);
+            }
+        }
+        Err(e) => log::error!("Failed to record transcription in history: {}", e),
+    }
+
+    _ = state.transition(&app_handle, AppState::Pasting);
+
+    let mut failed = Vec::new();
+    let mut pasted = false;
+    for sink in &sinks {
+        match sink
+            .deliver(
+                &app_handle,
+                &transcription,
+                &config.output,
+                paste_from_clipboard,
+            )
+            .await
+        {
+            Ok(()) => pasted |= *sink == OutputSink::Clipboard,
+            Err(e) => {
+                log::error!("Failed to deliver transcription to {:?}: {}", sink, e);
+                failed.push(format!("{}: {}", sink.name(), e));
+            }
         }
-        is_cleansing.0 = true;
-        drop(is_cleansing);
+    }
 
-        app_handle
-            .state::<TranscribeIcon>()
-            .change_icon(Icon::Cleansing);
+    // Typing is many edits, which one undo doesn't take back
+    let pasted = pasted && paste_from_clipboard && !sinks.contains(&OutputSink::Type);
+    set_last_pasted(&app_handle, pasted);
+
+    if failed.is_empty() {
+        state.finish(&app_handle);
+    } else {
+        state.fail(&app_handle, ErrorCode::DeliveryFailed, failed.join("; "));
+    }
+    log::info!("exiting toggle recording function");
+}
+
+/// Copies the selection in the focused window and returns it with what the
+/// clipboard held before. Returns `None` if nothing was selected, after putting
+/// the clipboard back.
+async fn copy_selection(app_handle: &AppHandle) -> Option<(String, ClipboardSnapshot)> {
+    let snapshot = ClipboardSnapshot::take(app_handle);
+
+    // Clearing first makes the copy observable even when the selection matches
+    // what was already in the clipboard
+    if let Err(e) = app_handle.clipboard().clear() {
+        log::error!("Failed to clear clipboard: {}", e);
+    }
+
+    let (tx_copy, rx_copy) = oneshot::channel::<()>();
+    let tx_task = app_handle.state::<mpsc::Sender<Task>>();
+    if let Err(e) = tx_task.send(Task::CopySelection(tx_copy)).await {
+        log::error!("Failed to send 'CopySelection' task to channel: {}", e);
+        put_back_clipboard(app_handle, Some(snapshot));
+        return None;
+    }
+    _ = rx_copy.await; // Wait for the copy keystrokes to be sent
+
+    // The focused app updates the clipboard on its own time
+    for _ in 0..25 {
+        if let Some(text) = app_handle
+            .clipboard()
+            .read_text()
+            .ok()
+            .filter(|text| !text.trim().is_empty())
+        {
+            return Some((text, snapshot));
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    put_back_clipboard(app_handle, Some(snapshot));
+    None
+}
+
+/// Undoes the last paste in the focused window and waits until it's done
+async fn undo_text(tx_task: &mpsc::Sender<Task>) {
+    let (tx_undo, rx_undo) = oneshot::channel::<()>();
+    match tx_task.send(Task::UndoText(tx_undo)).await {
+        Ok(()) => _ = rx_undo.await,
+        Err(e) => log::error!("Failed to send 'UndoText' task to channel: {}", e),
+    }
+}
+
+/// Erases what was typed of a polish that failed or was cancelled, and types
+/// the text it was replacing back in
+async fn take_back_typed_text(
+    app_handle: &AppHandle,
+    typed: usize,
+    original: &str,
+    key_delay: Duration,
+) {
+    if typed == 0 {
+        return;
+    }
+    set_last_pasted(app_handle, false);
+
+    let tasks = [
+        Task::EraseText {
+            chars: typed,
+            key_delay,
+        },
+        Task::TypeText {
+            text: original.to_string(),
+            key_delay,
+        },
+    ];
+    let tx_task = app_handle.state::<mpsc::Sender<Task>>();
+    for task in tasks {
+        if let Err(e) = tx_task.send(task).await {
+            log::error!("Failed to send task to channel: {}", e);
+            return;
+        }
+    }
+}
+
+/// Puts back the clipboard `copy_selection` replaced, when polishing the
+/// selection didn't go through
+fn put_back_clipboard(app_handle: &AppHandle, snapshot: Option<ClipboardSnapshot>) {
+    let Some(snapshot) = snapshot else {
+        return;
+    };
+    if let Err(e) = snapshot.put_back(app_handle) {
+        log::error!("Failed to put the clipboard back: {}", e);
+    }
+}
+
+pub fn cleanse_clipboard(
+    app_handle: AppHandle,
+    target: PolishTarget,
+    preset: Option<PolishPreset>,
+) {
+    spawn(async move {
+        // What the clipboard held before the selection was copied through it
+        let mut snapshot = None;
+        let clipboard_text = match target {
+            PolishTarget::Selection => {
+                let Some((selection, before)) = copy_selection(&app_handle).await else {
+                    log::warn!("Nothing selected to polish");
+                    AppNotifications::new(&app_handle)
+                        .notify(Notification::NothingSelected);
+                    PipelineEvent::error(
+                        ErrorCode::NothingSelected,
+                        t(Msg::NothingSelectedToPolish),
+                    )
+                    .emit(&app_handle);
+                    return;
+                };
+                snapshot = Some(before);
+                selection
+            }
+            PolishTarget::LastPaste => {
+                let last = app_handle.state::<Mutex<LastDictation>>();
+                let last = last.lock().unwrap();
+                let transcription = last.transcription.clone();
+                let pasted = last.pasted;
+                drop(last);
+
+                let Some(transcription) = transcription else {
+                    log::warn!("No transcript to polish");
+                    AppNotifications::new(&app_handle)
+                        .notify(Notification::NothingToRepeat);
+                    PipelineEvent::error(
+                        ErrorCode::NothingToRepeat,
+                        t(Msg::NoTranscriptToPolish),
+                    )
+                    .emit(&app_handle);
+                    return;
+                };
+                // Undoing anything else would take back whatever the user did last
+                if !pasted {
+                    log::warn!("Last transcript wasn't pasted, not replacing it");
+                    PipelineEvent::error(
+                        ErrorCode::NothingToRepeat,
+                        t(Msg::LastTranscriptNotPasted),
+                    )
+                    .emit(&app_handle);
+                    return;
+                }
+                transcription.output().to_string()
+            }
+            PolishTarget::Clipboard => {
+                let Ok(clipboard_text) = app_handle.clipboard().read_text() else {
+                    log::error!("Failed to read from clipboard");
+                    return;
+                };
+                clipboard_text
+            }
+        };
+
+        if clipboard_text.is_empty() {
+            AppNotifications::new(&app_handle).notify(Notification::EmptyClipboard);
+            PipelineEvent::error(ErrorCode::EmptyClipboard, t(Msg::ClipboardEmpty))
+                .emit(&app_handle);
+            return;
+        }
+
+        let state = app_handle.state::<StateMachine>();
+        if state.transition(&app_handle, AppState::Polishing).is_err() {
+            put_back_clipboard(&app_handle, snapshot);
+            return;
+        }
+        PipelineEvent::PolishStarted.emit(&app_handle);
 
         log::info!("Starting polish of: {}", clipboard_text.yellow());
 
         let app_handle_ = app_handle.clone();
         spawn(async move {
+            let state = app_handle_.state::<StateMachine>();
             let client = app_handle_.state::<TranscribeClient>();
+            let config = app_handle_
+                .state::<Mutex<AppConfig>>()
+                .lock()
+                .unwrap()
+                .clone();
 
             AppNotifications::new(&app_handle_).notify(Notification::StartPolishing);
 
-            let Ok(cleansed_text) = client.clean_transcription(clipboard_text).await else {
-                log::error!("Failed to clean transcription");
-                AppNotifications::new(&app_handle_).notify(Notification::ApiError);
-                app_handle_
-                    .state::<TranscribeIcon>()
-                    .change_icon(Icon::Default);
-                app_handle_.state::<Mutex<IsCleansing>>().lock().unwrap().0 = false;
+            let tx_task = app_handle_.state::<mpsc::Sender<Task>>();
+            let cancel = app_handle_.state::<InFlight>().token();
+
+            // The reply is typed as it streams in, so the text it replaces has to
+            // be gone before the first token is typed. A selection is replaced by
+            // the first typed character. Backends that don't stream get pasted.
+            let type_progressively = target != PolishTarget::Clipboard
+                && config.progressive_paste
+                && config.streams_polish(preset.as_ref());
+
+            let key_delay = Duration::from_millis(config.output.key_delay_ms);
+            let started_at = Local::now();
+            let (tx_delta, mut rx_delta) = mpsc::unbounded_channel::<String>();
+            let app_handle_progress = app_handle_.clone();
+            let cancel_progress = cancel.clone();
+            let vocabulary = config.vocabulary.clone();
+            let progress = spawn(async move {
+                let transcribe_icon = app_handle_progress.state::<TranscribeIcon>();
+                let tx_task = app_handle_progress.state::<mpsc::Sender<Task>>();
+                // Characters typed so far, to take them back if polishing fails
+                let mut typed = 0;
+                let mut type_text = async |text: String| {
+                    if !type_progressively
+                        || text.is_empty()
+                        || cancel_progress.is_cancelled()
+                    {
+                        return;
+                    }
+                    // The last paste stays until there's something to replace it
+                    if typed == 0 && target == PolishTarget::LastPaste {
+                        undo_text(&tx_task).await;
+                    }
+                    typed += text.chars().filter(|&c| c != '\r').count();
+                    _ = tx_task.send(Task::TypeText { text, key_delay }).await;
+                };
+
+                // Typed text gets the same spelling fixes as pasted text
+                let mut corrected = VocabularyStream::new(&vocabulary);
+                let mut received = 0;
+                while let Some(delta) = rx_delta.recv().await {
+                    received += delta.chars().count();
+                    transcribe_icon.set_progress(Some(&format!("{received} chars")));
+                    type_text(corrected.push(&delta)).await;
+                }
+                type_text(corrected.finish()).await;
+                transcribe_icon.set_progress(None);
+                typed
+            });
+
+            let result = cancel
+                .run_until_cancelled(client.polish(
+                    &config,
+                    preset.as_ref(),
+                    clipboard_text.clone(),
+                    move |delta| {
+                        _ = tx_delta.send(delta.to_string());
+                    },
+                ))
+                .await;
+
+            // Wait for the streamed text to be typed
+            let typed = progress.await.unwrap_or_default();
+
+            // Also covers a cancel that lands after the response arrived
+            let Some(result) = result.filter(|_| !cancel.is_cancelled()) else {
+                log::info!("Polishing cancelled");
+                take_back_typed_text(&app_handle_, typed, &clipboard_text, key_delay)
+                    .await;
+                put_back_clipboard(&app_handle_, snapshot);
+                state.finish(&app_handle_);
                 return;
             };
 
+            let cleansed_text = match result {
+                Ok(cleansed_text) => cleansed_text,
+                Err(e) => {
+                    log::error!("Failed to clean transcription: {}", e);
+                    AppNotifications::new(&app_handle_).notify(Notification::ApiError);
+                    take_back_typed_text(&app_handle_, typed, &clipboard_text, key_delay)
+                        .await;
+                    put_back_clipboard(&app_handle_, snapshot);
+                    state.fail(&app_handle_, ErrorCode::PolishFailed, e.to_string());
+                    return;
+                }
+            };
+            // Nothing was typed if the reply didn't stream after all, so it's
+            // pasted instead
+            let typed_progressively = typed > 0;
+
+            let cleansed_text =
+                vocabulary::apply_vocabulary(&cleansed_text, &config.vocabulary);
+
             log::info!("Polished text: {}", cleansed_text.to_string().yellow());
+            PipelineEvent::PolishResult {
+                original: clipboard_text.clone(),
+                text: cleansed_text.clone(),
+            }
+            .emit(&app_handle_);
 
-            app_handle_.clipboard().write_text(cleansed_text).unwrap();
+            let backend = config.polishing_backend(preset.as_ref());
+            let sink = if typed_progressively {
+                "type"
+            } else {
+                "clipboard"
+            };
+            let item = NewHistoryItem {
+                kind: HistoryKind::Polish,
+                raw_text: &clipboard_text,
+                polished_text: Some(&cleansed_text),
+                duration: None,
+                backend: backend.as_deref(),
+                language: None,
+                sinks: vec![sink],
+                started_at,
+            };
+            if let Err(e) = app_handle_.state::<History>().record(&item) {
+                log::error!("Failed to record polish in history: {}", e);
+            }
+            app_handle_
+                .state::<Recent>()
+                .push(&app_handle_, &cleansed_text);
 
-            if !paste_from_clipboard {
-                AppNotifications::new(&app_handle_).notify(Notification::PolishSuccess);
-                app_handle_.state::<Mutex<IsCleansing>>().lock().unwrap().0 = false;
-                app_handle_
-                    .state::<TranscribeIcon>()
-                    .change_icon(Icon::Default);
+            _ = state.transition(&app_handle_, AppState::Pasting);
+            // Pasting goes through the clipboard too, so what it held before is
+            // only put back when asked to, like after pasting a transcript
+            let snapshot = snapshot.or_else(|| {
+                (target != PolishTarget::Clipboard && config.output.restore_clipboard)
+                    .then(|| ClipboardSnapshot::take(&app_handle_))
+            });
+            if let Err(e) = app_handle_.clipboard().write_text(cleansed_text.clone()) {
+                log::error!("Failed to write polished text to clipboard: {}", e);
+                put_back_clipboard(&app_handle_, snapshot);
+                state.fail(&app_handle_, ErrorCode::DeliveryFailed, e.to_string());
                 return;
             }
 
-            let tx_task = app_handle_.state::<mpsc::Sender<Task>>();
-            let (tx_undo, rx_undo) = oneshot::channel::<()>();
+            if target == PolishTarget::Clipboard {
+                AppNotifications::new(&app_handle_).notify(Notification::PolishSuccess {
+                    original: clipboard_text,
+                    polished: cleansed_text,
+                });
+                state.finish(&app_handle_);
+                return;
+            }
 
-            tx_task.send(Task::UndoText(tx_undo)).await.unwrap();
+            if !typed_progressively {
+                if target == PolishTarget::LastPaste {
+                    undo_text(&tx_task).await;
+                }
 
-            _ = rx_undo.await; // Wait for the undo future to complete
+                if let Err(e) = tx_task.send(Task::PasteFromClipboard).await {
+                    log::error!("Failed to send 'PasteFromClipboard' task: {}", e);
+                    put_back_clipboard(&app_handle_, snapshot);
+                    state.fail(&app_handle_, ErrorCode::DeliveryFailed, e.to_string());
+                    return;
+                }
+            }
+            // The polished text can replace the last paste again, unless it was
+            // typed or a selection was pasted over since
+            set_last_pasted(
+                &app_handle_,
+                target == PolishTarget::LastPaste && !typed_progressively,
+            );
 
-            tx_task.send(Task::PasteFromClipboard).await.unwrap();
+            if let Some(snapshot) = snapshot.filter(|_| config.output.restore_clipboard) {
+                let delay = Duration::from_millis(config.output.restore_delay_ms);
+                tokio::time::sleep(delay).await;
+                if let Err(e) = snapshot.restore(&app_handle_, &cleansed_text) {
+                    log::error!("Failed to restore clipboard: {}", e);
+                }
+            }
 
-            app_handle_
-                .state::<TranscribeIcon>()
-                .change_icon(Icon::Default);
-            app_handle_.state::<Mutex<IsCleansing>>().lock().unwrap().0 = false;
+            state.finish(&app_handle_);
 
-            log::info!("Cleansing complete. Set 'IsCleansing' to false");
+            log::info!("Cleansing complete");
         });
     });
 }
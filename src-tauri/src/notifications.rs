@@ -1,14 +1,103 @@
-use tauri::AppHandle;
+use crate::{
+    config::{AppConfig, NotificationConfig},
+    i18n::{Msg, t},
+    recent::single_line,
+};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_notification::NotificationExt;
 
+/// How long a notification with buttons stays up. Each one has a thread waiting
+/// for a click, which only returns once the notification is gone.
+#[cfg(target_os = "linux")]
+const ACTIONS_TIMEOUT_MS: u32 = 30_000;
+
 pub enum Notification {
     StartPolishing,
-    PolishSuccess,
-    TranscribeSuccess, // when not pasting from clipboard
+    PolishSuccess {
+        original: String,
+        polished: String,
+    },
+    TranscribeSuccess {
+        text: String,
+    }, // when not pasting from clipboard
+    /// Transcribing or translating a recording failed, so it can be retried
+    TranscriptionError,
     ApiError,
     #[allow(dead_code)]
     AccessibilityError,
     CancelledSilence,
+    NothingSelected,
+    NothingToRepeat,
+    EmptyClipboard,
+}
+
+impl Notification {
+    fn is_enabled(&self, config: &NotificationConfig) -> bool {
+        let events = &config.events;
+        match self {
+            Notification::StartPolishing => events.start_polishing,
+            Notification::PolishSuccess { .. } => events.polish_success,
+            Notification::TranscribeSuccess { .. } => events.transcribe_success,
+            Notification::TranscriptionError => events.transcription_error,
+            Notification::ApiError => events.api_error,
+            Notification::AccessibilityError => events.accessibility_error,
+            Notification::CancelledSilence => events.cancelled_silence,
+            Notification::NothingSelected => events.nothing_selected,
+            Notification::NothingToRepeat => events.nothing_to_repeat,
+            Notification::EmptyClipboard => events.empty_clipboard,
+        }
+    }
+
+    /// Buttons on the notification, on platforms that support them
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn actions(&self) -> Vec<Action> {
+        match self {
+            Notification::PolishSuccess { original, .. } => {
+                vec![Action::CopyOriginal(original.clone())]
+            }
+            Notification::TranscriptionError => vec![Action::Retry],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+enum Action {
+    /// Transcribes the last recording again
+    Retry,
+    /// Puts the text from before polishing back in the clipboard
+    CopyOriginal(String),
+}
+
+#[cfg(target_os = "linux")]
+impl Action {
+    fn id(&self) -> &'static str {
+        match self {
+            Action::Retry => "retry",
+            Action::CopyOriginal(_) => "copy_original",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Action::Retry => t(Msg::Retry),
+            Action::CopyOriginal(_) => t(Msg::CopyOriginal),
+        }
+    }
+
+    fn run(self, app_handle: &AppHandle) {
+        use tauri_plugin_clipboard_manager::ClipboardExt;
+
+        match self {
+            Action::Retry => crate::retry_last(app_handle.clone(), false),
+            Action::CopyOriginal(text) => {
+                if let Err(e) = app_handle.clipboard().write_text(text) {
+                    log::error!("Failed to write text to clipboard: {}", e);
+                }
+            }
+        }
+    }
 }
 
 pub struct AppNotifications<'a> {
@@ -21,34 +110,114 @@ impl<'a> AppNotifications<'a> {
     }
 
     pub fn notify(&self, notification: Notification) {
-        let notifs = self.app_handle.notification().builder();
-        if let Err(e) = match notification {
-            Notification::PolishSuccess => notifs
-                .title("Polishing complete")
-                .body("Your polished text is ready and in your clipboard")
-                .show(),
-            Notification::StartPolishing => notifs
-                .title("Loading...")
-                .body("We're starting to polish your text. Please wait")
-                .show(),
-            Notification::TranscribeSuccess => notifs
-                .title("Transcription complete")
-                .body("Your transcription is ready in your clipboard")
-                .show(),
-            Notification::ApiError => notifs
-                .title("Error")
-                .body("Failed to connect to the API. Please try again later")
-                .show(),
-            Notification::AccessibilityError => notifs
-                .title("Error")
-                .body("Please grant accessibility permissions to the app and restart it")
-                .show(),
-            Notification::CancelledSilence => notifs
-                .title("Recording cancelled")
-                .body("No sound detected for a while, recording cancelled")
-                .show(),
-        } {
+        let config = self
+            .app_handle
+            .state::<Mutex<AppConfig>>()
+            .lock()
+            .unwrap()
+            .notifications
+            .clone();
+
+        if config.do_not_disturb || !notification.is_enabled(&config) {
+            return;
+        }
+
+        let preview = |text: &str| {
+            config
+                .preview
+                .then(|| single_line(text, config.preview_length))
+        };
+        let (title, body) = match &notification {
+            Notification::PolishSuccess { polished, .. } => (
+                t(Msg::PolishSuccessTitle),
+                preview(polished).unwrap_or(t(Msg::PolishSuccessBody).into()),
+            ),
+            Notification::StartPolishing => (
+                t(Msg::StartPolishingTitle),
+                t(Msg::StartPolishingBody).into(),
+            ),
+            Notification::TranscribeSuccess { text } => (
+                t(Msg::TranscribeSuccessTitle),
+                preview(text).unwrap_or(t(Msg::TranscribeSuccessBody).into()),
+            ),
+            Notification::TranscriptionError => {
+                (t(Msg::Error), t(Msg::TranscriptionErrorBody).into())
+            }
+            Notification::ApiError => (t(Msg::Error), t(Msg::ApiErrorBody).into()),
+            Notification::AccessibilityError => {
+                (t(Msg::Error), t(Msg::AccessibilityErrorBody).into())
+            }
+            Notification::CancelledSilence => (
+                t(Msg::CancelledSilenceTitle),
+                t(Msg::CancelledSilenceBody).into(),
+            ),
+            Notification::NothingSelected => (
+                t(Msg::NothingSelectedTitle),
+                t(Msg::NothingSelectedBody).into(),
+            ),
+            Notification::NothingToRepeat => (
+                t(Msg::NothingToRepeatTitle),
+                t(Msg::NothingToRepeatBody).into(),
+            ),
+            Notification::EmptyClipboard => (
+                t(Msg::EmptyClipboardTitle),
+                t(Msg::EmptyClipboardBody).into(),
+            ),
+        };
+
+        #[cfg(target_os = "linux")]
+        {
+            let actions = notification.actions();
+            if !actions.is_empty() {
+                if let Err(e) = self.show_with_actions(title, &body, actions) {
+                    log::error!("Failed to trigger notification: {}", e);
+                }
+                return;
+            }
+        }
+
+        if let Err(e) = self
+            .app_handle
+            .notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+        {
             log::error!("Failed to trigger notification: {}", e);
         }
     }
+
+    /// The notification plugin only supports actions on mobile, so this talks
+    /// to the notification server directly
+    #[cfg(target_os = "linux")]
+    fn show_with_actions(
+        &self,
+        title: &str,
+        body: &str,
+        actions: Vec<Action>,
+    ) -> anyhow::Result<()> {
+        let mut notification = notify_rust::Notification::new();
+        notification
+            .appname(&self.app_handle.package_info().name)
+            .summary(title)
+            .body(body)
+            .timeout(notify_rust::Timeout::Milliseconds(ACTIONS_TIMEOUT_MS));
+        for action in &actions {
+            notification.action(action.id(), action.label());
+        }
+        let handle = notification.show()?;
+
+        // Blocks until the notification is clicked, closed or expires
+        let app_handle = self.app_handle.clone();
+        std::thread::spawn(move || {
+            handle.wait_for_action(|id| {
+                if let Some(action) = actions.into_iter().find(|a| a.id() == id) {
+                    action.run(&app_handle);
+                }
+            });
+        });
+
+        Ok(())
+    }
 }
@@ -0,0 +1,162 @@
+use crate::{
+    clipboard_snapshot::ClipboardSnapshot,
+    config::{OutputConfig, expand_home},
+    local_task_handler::Task,
+    notifications::{AppNotifications, Notification},
+    transcribe_client::{TranscribeClient, Transcription},
+};
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    fs::{OpenOptions, create_dir_all},
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tokio::sync::mpsc;
+
+/// Where a finished transcription goes. Written as `"clipboard"`, `"type"` or
+/// `"stdout"`, or as `{ "file": "<path>" }` and `{ "webhook": "<url>" }`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputSink {
+    /// Copy to the clipboard and paste into the focused window
+    Clipboard,
+    /// Type into the focused window key by key, leaving the clipboard alone
+    Type,
+    /// Append to a file, one timestamped line per transcription
+    File(PathBuf),
+    /// Print to standard output, for when the app is started from a terminal
+    Stdout,
+    /// POST the transcription as JSON
+    Webhook(String),
+}
+
+impl OutputSink {
+    /// Short name recorded in the history
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Clipboard => "clipboard",
+            Self::Type => "type",
+            Self::File(_) => "file",
+            Self::Stdout => "stdout",
+            Self::Webhook(_) => "webhook",
+        }
+    }
+
+    /// `paste` is false when recording was toggled from the tray, in which case
+    /// there is no window to paste or type into
+    pub async fn deliver(
+        &self,
+        app_handle: &AppHandle,
+        transcription: &Transcription,
+        output: &OutputConfig,
+        paste: bool,
+    ) -> Result<()> {
+        let text = transcription.output();
+
+        match self {
+            Self::Clipboard => to_clipboard(app_handle, text, output, paste).await,
+            // The clipboard is the next best thing without a window to type into
+            Self::Type if !paste => to_clipboard(app_handle, text, output, false).await,
+            Self::Type => {
+                let task = Task::TypeText {
+                    text: text.to_string(),
+                    key_delay: Duration::from_millis(output.key_delay_ms),
+                };
+                app_handle
+                    .state::<mpsc::Sender<Task>>()
+                    .send(task)
+                    .await
+                    .map_err(|e| anyhow!("Failed to send 'TypeText' task: {e}"))
+            }
+            Self::File(path) => append_to_file(path, text),
+            Self::Stdout => {
+                println!("{text}");
+                Ok(())
+            }
+            Self::Webhook(url) => post_to_webhook(app_handle, url, transcription).await,
+        }
+    }
+}
+
+async fn to_clipboard(
+    app_handle: &AppHandle,
+    text: &str,
+    output: &OutputConfig,
+    paste: bool,
+) -> Result<()> {
+    let snapshot =
+        (paste && output.restore_clipboard).then(|| ClipboardSnapshot::take(app_handle));
+
+    app_handle.clipboard().write_text(text)?;
+
+    if !paste {
+        AppNotifications::new(app_handle).notify(Notification::TranscribeSuccess {
+            text: text.to_string(),
+        });
+        return Ok(());
+    }
+
+    app_handle
+        .state::<mpsc::Sender<Task>>()
+        .send(Task::PasteFromClipboard)
+        .await
+        .map_err(|e| anyhow!("Failed to send 'PasteFromClipboard' task: {e}"))?;
+    log::info!("Sent PasteFromClipboard task to channel");
+
+    if let Some(snapshot) = snapshot {
+        tokio::time::sleep(Duration::from_millis(output.restore_delay_ms)).await;
+        snapshot.restore(app_handle, text)?;
+        log::info!("Restored previous clipboard contents");
+    }
+
+    Ok(())
+}
+
+fn append_to_file(path: &Path, text: &str) -> Result<()> {
+    let path = expand_home(path)?;
+
+    if let Some(parent_dir) = path.parent() {
+        create_dir_all(parent_dir)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+    writeln!(file, "[{timestamp}] {text}")?;
+
+    Ok(())
+}
+
+async fn post_to_webhook(
+    app_handle: &AppHandle,
+    url: &str,
+    transcription: &Transcription,
+) -> Result<()> {
+    let body = json!({
+        "text": transcription.output(),
+        "transcript": transcription.text,
+        "translation": transcription.translation,
+        "backend": transcription.backend,
+        "timestamp": chrono::Local::now().to_rfc3339(),
+    });
+
+    app_handle
+        .state::<TranscribeClient>()
+        .http_client()
+        .post(url)
+        .timeout(Duration::from_secs(10))
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::i18n::{Msg, t};
+use anyhow::Result;
+use std::{collections::VecDeque, sync::Mutex};
+use tauri::{
+    AppHandle, Manager, Wry,
+    menu::{MenuItem, PredefinedMenuItem, Submenu},
+};
+
+/// Characters shown per transcript in the menu
+const LABEL_LENGTH: usize = 40;
+
+/// The last transcripts and polishes since the app started, newest first, listed
+/// under "Recent" in the tray menu. Menu item ids are `recent:<index>`. Older
+/// ones are in the history, so clearing this doesn't have to touch it.
+pub struct Recent {
+    items: Mutex<VecDeque<String>>,
+    limit: usize,
+    submenu: Submenu<Wry>,
+}
+
+impl Recent {
+    pub fn new<M: Manager<Wry>>(manager: &M, limit: usize) -> Result<Self> {
+        let recent = Self {
+            items: Mutex::default(),
+            limit,
+            submenu: Submenu::with_id(manager, "recent", t(Msg::Recent), true)?,
+        };
+        recent.rebuild(manager.app_handle())?;
+
+        Ok(recent)
+    }
+
+    pub fn submenu(&self) -> &Submenu<Wry> {
+        &self.submenu
+    }
+
+    pub fn get(&self, index: usize) -> Option<String> {
+        self.items.lock().unwrap().get(index).cloned()
+    }
+
+    pub fn push(&self, app_handle: &AppHandle, text: &str) {
+        let mut items = self.items.lock().unwrap();
+        items.push_front(text.to_string());
+        items.truncate(self.limit);
+        drop(items);
+
+        if let Err(e) = self.rebuild(app_handle) {
+            log::error!("Failed to update recent menu: {e}");
+        }
+    }
+
+    pub fn clear(&self, app_handle: &AppHandle) {
+        self.items.lock().unwrap().clear();
+
+        if let Err(e) = self.rebuild(app_handle) {
+            log::error!("Failed to update recent menu: {e}");
+        }
+    }
+
+    fn rebuild(&self, app_handle: &AppHandle) -> Result<()> {
+        while self.submenu.remove_at(0)?.is_some() {}
+
+        let items = self.items.lock().unwrap().clone();
+        for (index, text) in items.iter().enumerate() {
+            self.submenu.append(&MenuItem::with_id(
+                app_handle,
+                format!("recent:{index}"),
+                single_line(text, LABEL_LENGTH),
+                true,
+                None::<&str>,
+            )?)?;
+        }
+
+        self.submenu
+            .append(&PredefinedMenuItem::separator(app_handle)?)?;
+        self.submenu.append(&MenuItem::with_id(
+            app_handle,
+            "clear_recent",
+            t(Msg::ClearRecent),
+            !items.is_empty(),
+            None::<&str>,
+        )?)?;
+        self.submenu.set_enabled(!items.is_empty())?;
+
+        Ok(())
+    }
+}
+
+/// `text` on a single line, cut off after `max` characters
+pub fn single_line(text: &str, max: usize) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= max {
+        return line;
+    }
+
+    let truncated = line.chars().take(max).collect::<String>();
+    format!("{}…", truncated.trim_end())
+}
@@ -0,0 +1,172 @@
+use anyhow::{Context, Result, anyhow};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chacha20poly1305::{
+    AeadCore, ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, OsRng},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{OpenOptions, read_to_string},
+    io::Write,
+    path::PathBuf,
+    sync::Once,
+};
+
+const SERVICE: &str = "com.whistle.app";
+const NONCE_LEN: usize = 12;
+
+/// Every authenticated request reads a secret, so only mention a missing keyring once
+static KEYRING_UNAVAILABLE: Once = Once::new();
+
+/// A credential read from the secret store. Deliberately has no `Display` and
+/// a redacted `Debug` so it can't end up in the logs by accident.
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+/// Reads the secret stored for `backend`, preferring the OS secret store and
+/// falling back to the encrypted file (e.g. headless Linux without a keyring).
+pub fn get_secret(backend: &str) -> Result<Option<Secret>> {
+    match keyring::Entry::new(SERVICE, backend).and_then(|entry| entry.get_password()) {
+        Ok(secret) => return Ok(Some(Secret(secret))),
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => KEYRING_UNAVAILABLE.call_once(|| {
+            log::warn!("OS secret store unavailable, using encrypted file: {e}")
+        }),
+    }
+
+    Ok(read_secrets_file()?.remove(backend).map(Secret))
+}
+
+pub fn set_secret(backend: &str, secret: &str) -> Result<()> {
+    match keyring::Entry::new(SERVICE, backend)
+        .and_then(|entry| entry.set_password(secret))
+    {
+        Ok(()) => {
+            // Don't leave a stale copy behind if we previously had to fall back
+            let mut secrets = read_secrets_file()?;
+            if secrets.remove(backend).is_some() {
+                write_secrets_file(&secrets)?;
+            }
+            Ok(())
+        }
+        Err(e) => {
+            log::warn!("OS secret store unavailable, using encrypted file: {e}");
+            let mut secrets = read_secrets_file()?;
+            secrets.insert(backend.to_string(), secret.to_string());
+            write_secrets_file(&secrets)
+        }
+    }
+}
+
+pub fn delete_secret(backend: &str) -> Result<()> {
+    match keyring::Entry::new(SERVICE, backend)
+        .and_then(|entry| entry.delete_credential())
+    {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(e) => log::warn!("Failed to delete secret from OS secret store: {e}"),
+    }
+
+    let mut secrets = read_secrets_file()?;
+    if secrets.remove(backend).is_some() {
+        write_secrets_file(&secrets)?;
+    }
+
+    Ok(())
+}
+
+fn secrets_file_path() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .context("Could not find home directory")?
+        .join(".config/whistle/secrets.json"))
+}
+
+/// The file is encrypted with a key derived from the machine id, so copying it
+/// to another machine (or into a backup) doesn't leak the tokens in plain text.
+fn cipher() -> ChaCha20Poly1305 {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| read_to_string(path).ok())
+        .unwrap_or_else(|| {
+            dirs::home_dir()
+                .map(|home| home.display().to_string())
+                .unwrap_or_default()
+        });
+
+    let key = Sha256::new()
+        .chain_update(SERVICE)
+        .chain_update(machine_id.trim())
+        .finalize();
+
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+fn read_secrets_file() -> Result<HashMap<String, String>> {
+    let path = secrets_file_path()?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let encoded: HashMap<String, String> = serde_json::from_str(&read_to_string(path)?)?;
+    let cipher = cipher();
+
+    encoded
+        .into_iter()
+        .map(|(backend, value)| {
+            let bytes = BASE64_STANDARD.decode(value)?;
+            if bytes.len() < NONCE_LEN {
+                return Err(anyhow!("Malformed secret for backend '{backend}'"));
+            }
+            let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+            let plaintext = cipher
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|_| {
+                    anyhow!("Failed to decrypt secret for backend '{backend}'")
+                })?;
+            Ok((backend, String::from_utf8(plaintext)?))
+        })
+        .collect()
+}
+
+fn write_secrets_file(secrets: &HashMap<String, String>) -> Result<()> {
+    let cipher = cipher();
+
+    let encoded = secrets
+        .iter()
+        .map(|(backend, secret)| {
+            let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+            let ciphertext = cipher.encrypt(&nonce, secret.as_bytes()).map_err(|_| {
+                anyhow!("Failed to encrypt secret for backend '{backend}'")
+            })?;
+            let bytes = [nonce.as_slice(), &ciphertext].concat();
+            Ok((backend.clone(), BASE64_STANDARD.encode(bytes)))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    // Owner-only from the start, rather than tightened after the tokens are written
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(secrets_file_path()?)?;
+    file.write_all(serde_json::to_string_pretty(&encoded)?.as_bytes())?;
+
+    Ok(())
+}
@@ -7,6 +7,36 @@ use tauri_plugin_global_shortcut::Shortcut;
 pub struct ShortcutsConfig {
     pub toggle_recording: Shortcut,
     pub cleanse_clipboard: Shortcut,
+    #[serde(default = "default_cancel_shortcut")]
+    pub cancel: Shortcut,
+    #[serde(default = "default_polish_selection_shortcut")]
+    pub polish_selection: Shortcut,
+    #[serde(default = "default_repaste_last_shortcut")]
+    pub repaste_last: Shortcut,
+    #[serde(default = "default_retry_last_shortcut")]
+    pub retry_last: Shortcut,
+    #[serde(default = "default_polish_last_paste_shortcut")]
+    pub polish_last_paste: Shortcut,
+}
+
+fn default_cancel_shortcut() -> Shortcut {
+    Shortcut::from_str("CmdOrCtrl+Option+X").unwrap()
+}
+
+fn default_polish_selection_shortcut() -> Shortcut {
+    Shortcut::from_str("CmdOrCtrl+Option+P").unwrap()
+}
+
+fn default_repaste_last_shortcut() -> Shortcut {
+    Shortcut::from_str("CmdOrCtrl+Option+V").unwrap()
+}
+
+fn default_retry_last_shortcut() -> Shortcut {
+    Shortcut::from_str("CmdOrCtrl+Option+Y").unwrap()
+}
+
+fn default_polish_last_paste_shortcut() -> Shortcut {
+    Shortcut::from_str("CmdOrCtrl+Option+U").unwrap()
 }
 
 impl Default for ShortcutsConfig {
@@ -14,6 +44,11 @@ impl Default for ShortcutsConfig {
         Self {
             toggle_recording: Shortcut::from_str("CmdOrCtrl+Option+R").unwrap(),
             cleanse_clipboard: Shortcut::from_str("CmdOrCtrl+Option+C").unwrap(),
+            cancel: default_cancel_shortcut(),
+            polish_selection: default_polish_selection_shortcut(),
+            repaste_last: default_repaste_last_shortcut(),
+            retry_last: default_retry_last_shortcut(),
+            polish_last_paste: default_polish_last_paste_shortcut(),
         }
     }
 }
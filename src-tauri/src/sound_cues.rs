@@ -0,0 +1,94 @@
+use crate::config::{CueConfig, SoundConfig, expand_home};
+use anyhow::Result;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use std::{
+    io::Cursor,
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Cue {
+    Start,
+    Stop,
+    Success,
+    Error,
+}
+
+impl Cue {
+    fn bundled(self) -> &'static [u8] {
+        match self {
+            Cue::Start => include_bytes!("../sounds/start.wav"),
+            Cue::Stop => include_bytes!("../sounds/stop.wav"),
+            Cue::Success => include_bytes!("../sounds/success.wav"),
+            Cue::Error => include_bytes!("../sounds/error.wav"),
+        }
+    }
+
+    fn config(self, config: &SoundConfig) -> &CueConfig {
+        match self {
+            Cue::Start => &config.start,
+            Cue::Stop => &config.stop,
+            Cue::Success => &config.success,
+            Cue::Error => &config.error,
+        }
+    }
+}
+
+/// Plays cues on a thread of its own, so playing one never waits on the audio
+/// device
+pub struct SoundCues(Sender<Cue>);
+
+impl SoundCues {
+    pub fn new(config: SoundConfig) -> Self {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || play_cues(rx, config));
+        Self(tx)
+    }
+
+    pub fn play(&self, cue: Cue) {
+        if let Err(e) = self.0.send(cue) {
+            log::error!("Failed to queue {:?} sound: {}", cue, e);
+        }
+    }
+}
+
+fn play_cues(rx: Receiver<Cue>, config: SoundConfig) {
+    // Opened on the first enabled cue, so the output device isn't held when
+    // sounds are off. `OutputStream` has to stay alive while sounds play.
+    let mut output: Option<(OutputStream, OutputStreamHandle)> = None;
+
+    for cue in rx {
+        let cue_config = cue.config(&config);
+        if !cue_config.enabled {
+            continue;
+        }
+
+        if output.is_none() {
+            output = OutputStream::try_default()
+                .inspect_err(|e| log::error!("Failed to open audio output: {}", e))
+                .ok();
+        }
+        let Some((_, handle)) = &output else {
+            continue;
+        };
+
+        if let Err(e) = play(handle, cue, cue_config) {
+            log::error!("Failed to play {:?} sound: {}", cue, e);
+        }
+    }
+}
+
+fn play(handle: &OutputStreamHandle, cue: Cue, config: &CueConfig) -> Result<()> {
+    let wav = match &config.path {
+        Some(path) => std::fs::read(expand_home(path)?)?,
+        None => cue.bundled().to_vec(),
+    };
+
+    let sink = Sink::try_new(handle)?;
+    sink.set_volume(config.volume.clamp(0.0, 1.0));
+    sink.append(Decoder::new(Cursor::new(wav))?);
+    // Keeps playing after the sink is dropped
+    sink.detach();
+
+    Ok(())
+}
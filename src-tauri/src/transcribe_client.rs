@@ -1,44 +1,380 @@
-use crate::constants::API_BASE_URL;
-use anyhow::{Context, Result};
-use reqwest::Client;
-use serde::Deserialize;
+use crate::{
+    circuit_breaker::CircuitBreaker,
+    config::{
+        AppConfig, AuthScheme, BackendConfig, ChatCompletionsConfig, PolishPreset,
+        PolishingConfig, TranscriptionApi, TranscriptionBackendConfig,
+    },
+    secrets, vocabulary,
+};
+use anyhow::{Context, Result, bail};
+use futures_util::StreamExt;
+use reqwest::{
+    Client, RequestBuilder, StatusCode,
+    header::HeaderValue,
+    multipart::{Form, Part},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Mutex, time::Duration};
 
 #[derive(Debug, Deserialize)]
 struct TranscriptionResponse {
     text: String,
+    #[serde(default)]
     original_text: Option<String>,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatCompletionChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChoice {
+    message: ChatCompletionMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionMessage {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    choices: Vec<ChatCompletionChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunkChoice {
+    delta: ChatCompletionDelta,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionDelta {
+    content: Option<String>,
+}
+
+/// What was said and, when translating, what should be output instead
+#[derive(Debug, Clone)]
+pub struct Transcription {
+    /// The transcript in the spoken language. When the backend translated and
+    /// didn't return the original, this is the translation as well.
+    pub text: String,
+    pub translation: Option<String>,
+    /// Name of the backend that produced it
+    pub backend: String,
+    /// The spoken language, if the backend reported it
+    pub language: Option<String>,
+}
+
+impl Transcription {
+    /// The text that should end up in the target app
+    pub fn output(&self) -> &str {
+        self.translation.as_deref().unwrap_or(&self.text)
+    }
 }
 
 pub struct TranscribeClient {
     http_client: Client,
+    /// Keyed by backend name
+    breakers: Mutex<HashMap<String, CircuitBreaker>>,
 }
 
 impl TranscribeClient {
     pub fn new() -> Self {
         Self {
-            http_client: Client::new(),
+            http_client: Client::builder()
+                .connect_timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to build HTTP client"),
+            breakers: Mutex::new(HashMap::new()),
         }
     }
 
-    pub async fn fetch_transcription(&self, recording: Vec<u8>) -> Result<String> {
-        let res = self
-            .http_client
-            .post(format!("{API_BASE_URL}/transcribe-auto-detect"))
-            .header("Content-Type", "audio/wav")
-            .body(recording)
+    /// Shared with everything else that talks HTTP, so connections are reused
+    pub fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+
+    /// Tries each backend in order, skipping the ones whose circuit breaker is
+    /// open, until one of them returns a transcription
+    pub async fn transcribe(
+        &self,
+        backends: &[TranscriptionBackendConfig],
+        recording: Vec<u8>,
+        vocabulary: &[String],
+        language: Option<&str>,
+        translate: bool,
+    ) -> Result<Transcription> {
+        for backend in backends {
+            let name = &backend.backend.name;
+
+            if !self.breaker_allows_request(name) {
+                log::info!("Skipping backend '{name}', its circuit breaker is open");
+                continue;
+            }
+
+            let result = self
+                .fetch_transcription(
+                    backend,
+                    recording.clone(),
+                    vocabulary,
+                    language,
+                    translate,
+                )
+                .await;
+
+            let mut breakers = self.breakers.lock().unwrap();
+            let breaker = breakers.entry(name.clone()).or_default();
+
+            match result {
+                Ok(transcription) => {
+                    breaker.record_success();
+                    return Ok(transcription);
+                }
+                Err(e) => {
+                    log::error!("Backend '{name}' failed to transcribe: {e}");
+                    breaker.record_failure();
+                }
+            }
+        }
+
+        bail!("No transcription backend available")
+    }
+
+    /// `language` is the spoken language as an ISO-639-1 code, detected by the
+    /// backend when `None`
+    pub async fn fetch_transcription(
+        &self,
+        backend: &TranscriptionBackendConfig,
+        recording: Vec<u8>,
+        vocabulary: &[String],
+        language: Option<&str>,
+        translate: bool,
+    ) -> Result<Transcription> {
+        let prompt = vocabulary::initial_prompt(vocabulary);
+
+        let req = match backend.api {
+            TranscriptionApi::Whistle => {
+                let mut req = self
+                    .http_client
+                    .post(format!(
+                        "{}/transcribe-auto-detect",
+                        backend.backend.base_url
+                    ))
+                    .header("Content-Type", "audio/wav");
+
+                if let Some(prompt) = prompt {
+                    req = req.query(&[("initial_prompt", prompt)]);
+                }
+
+                if let Some(language) = language {
+                    req = req.query(&[("language", language)]);
+                }
+
+                if translate {
+                    req = req.query(&[("task", "translate")]);
+                }
+
+                req.body(recording)
+            }
+            TranscriptionApi::OpenAi => {
+                let endpoint = if translate {
+                    "translations"
+                } else {
+                    "transcriptions"
+                };
+                let model = backend.model.as_deref().unwrap_or("whisper-1");
+
+                let file = Part::bytes(recording)
+                    .file_name("recording.wav")
+                    .mime_str("audio/wav")?;
+                let mut form = Form::new()
+                    .part("file", file)
+                    .text("model", model.to_string());
+
+                if let Some(prompt) = prompt {
+                    form = form.text("prompt", prompt);
+                }
+
+                if let Some(language) = language {
+                    form = form.text("language", language.to_string());
+                }
+
+                self.http_client
+                    .post(format!("{}/audio/{endpoint}", backend.backend.base_url))
+                    .multipart(form)
+            }
+        };
+
+        let res = authorize(req, &backend.backend)?
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
 
         let res: TranscriptionResponse = res.json().await?;
 
-        Ok(res.text)
+        if !translate {
+            return Ok(Transcription {
+                text: res.text,
+                translation: None,
+                backend: backend.backend.name.clone(),
+                language: res.language,
+            });
+        }
+
+        Ok(Transcription {
+            text: res.original_text.unwrap_or_else(|| res.text.clone()),
+            translation: Some(res.text),
+            backend: backend.backend.name.clone(),
+            language: res.language,
+        })
+    }
+
+    /// The first backend in the chain whose circuit breaker is closed
+    pub fn healthy_backend<'a>(
+        &self,
+        backends: &'a [TranscriptionBackendConfig],
+    ) -> Option<&'a str> {
+        let breakers = self.breakers.lock().unwrap();
+        backends
+            .iter()
+            .map(|backend| backend.backend.name.as_str())
+            .find(|name| breakers.get(*name).is_none_or(|breaker| !breaker.is_open()))
+    }
+
+    /// Sends a probe to every backend whose breaker is open and due for one. A
+    /// backend that answers with a success is considered healthy again.
+    pub async fn probe_backends(&self, backends: &[TranscriptionBackendConfig]) {
+        for backend in backends {
+            let name = &backend.backend.name;
+
+            let due = self
+                .breakers
+                .lock()
+                .unwrap()
+                .get_mut(name)
+                .is_some_and(|breaker| breaker.is_open() && breaker.allows_request());
+            if !due {
+                continue;
+            }
+
+            let req = self
+                .http_client
+                .get(&backend.backend.base_url)
+                .timeout(Duration::from_secs(5));
+            let healthy = match authorize(req, &backend.backend) {
+                Ok(req) => req.send().await.is_ok_and(|res| {
+                    // The base URL may only take POSTs, which still shows the
+                    // server is up and the request got past authentication
+                    res.status().is_success()
+                        || res.status() == StatusCode::METHOD_NOT_ALLOWED
+                }),
+                Err(_) => false,
+            };
+
+            log::info!("Probed backend '{name}', healthy: {healthy}");
+
+            let mut breakers = self.breakers.lock().unwrap();
+            let breaker = breakers.entry(name.clone()).or_default();
+            if healthy {
+                breaker.record_success();
+            } else {
+                breaker.record_failure();
+            }
+        }
+    }
+
+    fn breaker_allows_request(&self, backend: &str) -> bool {
+        self.breakers
+            .lock()
+            .unwrap()
+            .get_mut(backend)
+            .is_none_or(|breaker| breaker.allows_request())
+    }
+
+    /// Translates `text` with the `chat_completions` polishing backend
+    pub async fn translate(
+        &self,
+        polishing: &PolishingConfig,
+        text: &str,
+        target_language: &str,
+    ) -> Result<String> {
+        let PolishingConfig::ChatCompletions(chat) = polishing else {
+            bail!("Translating needs a `chat_completions` polishing backend");
+        };
+
+        let chat = ChatCompletionsConfig {
+            system_prompt: format!(
+                "Translate the user's text into {target_language}. Keep the tone and \
+                 formatting. Reply with the translation only."
+            ),
+            stream: false,
+            ..chat.clone()
+        };
+
+        self.chat_completion(&chat, text, |_| {}).await
+    }
+
+    /// Polishes `text` with the preset if one is given, otherwise with whichever
+    /// backend is configured. `on_delta` only gets called by streaming backends.
+    pub async fn polish(
+        &self,
+        config: &AppConfig,
+        preset: Option<&PolishPreset>,
+        text: String,
+        on_delta: impl FnMut(&str),
+    ) -> Result<String> {
+        if let Some(preset) = preset {
+            let chat = preset.resolve(&config.polishing)?;
+            return self.chat_completion(&chat, &text, on_delta).await;
+        }
+
+        match &config.polishing {
+            PolishingConfig::Whistle => {
+                let backend = config
+                    .transcription_backends
+                    .iter()
+                    .find(|backend| backend.api == TranscriptionApi::Whistle)
+                    .context("Polishing needs a `whistle` transcription backend")?;
+                self.clean_transcription(&backend.backend, text).await
+            }
+            PolishingConfig::ChatCompletions(chat) => {
+                self.chat_completion(chat, &text, on_delta).await
+            }
+        }
     }
 
-    pub async fn clean_transcription(&self, transcription: String) -> Result<String> {
-        let res = self
+    pub async fn clean_transcription(
+        &self,
+        backend: &BackendConfig,
+        transcription: String,
+    ) -> Result<String> {
+        let req = self
             .http_client
-            .post(format!("{API_BASE_URL}/clean-transcription"))
-            .header("Content-Type", "application/json")
+            .post(format!("{}/clean-transcription", backend.base_url))
+            .header("Content-Type", "application/json");
+
+        let res = authorize(req, backend)?
             .body(serde_json::json!({ "text": transcription }).to_string())
             .send()
             .await?;
@@ -50,4 +386,221 @@ impl TranscribeClient {
 
         Ok(response.text)
     }
+
+    pub async fn chat_completion(
+        &self,
+        chat: &ChatCompletionsConfig,
+        text: &str,
+        mut on_delta: impl FnMut(&str),
+    ) -> Result<String> {
+        let body = ChatCompletionRequest {
+            model: &chat.model,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: &chat.system_prompt,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: text,
+                },
+            ],
+            temperature: chat.temperature,
+            max_tokens: chat.max_tokens,
+            stream: chat.stream,
+        };
+
+        let req = self
+            .http_client
+            .post(format!("{}/chat/completions", chat.backend.base_url))
+            .json(&body);
+
+        let res = authorize(req, &chat.backend)?
+            .send()
+            .await?
+            .error_for_status()?;
+
+        if chat.stream {
+            let mut stream = res.bytes_stream();
+            let mut reply = ChatCompletionStream::default();
+            while let Some(chunk) = stream.next().await {
+                reply.push(&chunk?, &mut on_delta)?;
+                if reply.done {
+                    break;
+                }
+            }
+
+            return Ok(reply.finish());
+        }
+
+        let response: ChatCompletionResponse = res.json().await?;
+
+        let choice = response
+            .choices
+            .into_iter()
+            .next()
+            .context("Chat completion returned no choices")?;
+
+        Ok(choice.message.content.trim().to_string())
+    }
+}
+
+/// Collects a streamed chat completion. Server-sent events: one `data: <json>`
+/// line per chunk, terminated by `data: [DONE]`, split across network reads
+/// at arbitrary points.
+#[derive(Debug, Default)]
+struct ChatCompletionStream {
+    /// The start of a line that hasn't been received completely
+    buffer: Vec<u8>,
+    reply: String,
+    done: bool,
+}
+
+impl ChatCompletionStream {
+    /// Calls `on_delta` with the text of every complete chunk in `bytes`
+    fn push(&mut self, bytes: &[u8], on_delta: &mut impl FnMut(&str)) -> Result<()> {
+        self.buffer.extend_from_slice(bytes);
+
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line = self.buffer.drain(..=newline).collect::<Vec<_>>();
+            let line = String::from_utf8_lossy(&line);
+
+            if self.done {
+                continue;
+            }
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+            if data.trim() == "[DONE]" {
+                self.done = true;
+                continue;
+            }
+
+            let chunk: ChatCompletionChunk = serde_json::from_str(data.trim())?;
+            let Some(delta) = chunk
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.delta.content)
+            else {
+                continue;
+            };
+
+            let delta = if self.reply.is_empty() {
+                delta.trim_start()
+            } else {
+                &delta
+            };
+            if !delta.is_empty() {
+                on_delta(delta);
+                self.reply.push_str(delta);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> String {
+        self.reply.trim_end().to_string()
+    }
+}
+
+/// Attaches the backend's stored secret to the request. The header value is
+/// marked sensitive so it is redacted from `Debug` output.
+fn authorize(req: RequestBuilder, backend: &BackendConfig) -> Result<RequestBuilder> {
+    let Some(auth) = &backend.auth else {
+        return Ok(req);
+    };
+
+    let secret = secrets::get_secret(&backend.name)?
+        .with_context(|| format!("No secret stored for backend '{}'", backend.name))?;
+
+    let req = match auth {
+        AuthScheme::Bearer => req.bearer_auth(secret.expose()),
+        AuthScheme::Header { name } => {
+            let mut value = HeaderValue::from_str(secret.expose())
+                .context("Secret is not a valid header value")?;
+            value.set_sensitive(true);
+            req.header(name, value)
+        }
+    };
+
+    Ok(req)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(content: &str) -> String {
+        let chunk =
+            serde_json::json!({ "choices": [{ "delta": { "content": content } }] });
+        format!("data: {chunk}\n\n")
+    }
+
+    /// Feeds `bytes` in pieces of `size` and returns the deltas and the reply
+    fn collect(bytes: &[u8], size: usize) -> (Vec<String>, String) {
+        let mut stream = ChatCompletionStream::default();
+        let mut deltas = Vec::new();
+        for piece in bytes.chunks(size) {
+            stream
+                .push(piece, &mut |delta| deltas.push(delta.to_string()))
+                .unwrap();
+        }
+        (deltas, stream.finish())
+    }
+
+    #[test]
+    fn joins_deltas_split_across_reads() {
+        let body = [chunk("Hello"), chunk(", wörld"), "data: [DONE]\n\n".into()].concat();
+
+        for size in [1, 3, 7, body.len()] {
+            let (deltas, reply) = collect(body.as_bytes(), size);
+            assert_eq!(deltas, ["Hello", ", wörld"], "read size {size}");
+            assert_eq!(reply, "Hello, wörld");
+        }
+    }
+
+    #[test]
+    fn skips_comments_and_chunks_without_content() {
+        let body = [
+            ": keep-alive\r\n\r\n".to_string(),
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\r\n\r\n".into(),
+            chunk("Hi"),
+        ]
+        .concat();
+
+        let (deltas, reply) = collect(body.as_bytes(), body.len());
+        assert_eq!(deltas, ["Hi"]);
+        assert_eq!(reply, "Hi");
+    }
+
+    #[test]
+    fn trims_the_reply() {
+        let body = [chunk("\n  Hi"), chunk(" there"), chunk("\n")].concat();
+
+        let (deltas, reply) = collect(body.as_bytes(), body.len());
+        assert_eq!(deltas, ["Hi", " there", "\n"]);
+        assert_eq!(reply, "Hi there");
+    }
+
+    #[test]
+    fn ignores_everything_after_done() {
+        let body = [chunk("Hi"), "data: [DONE]\n\n".into(), chunk("ignored")].concat();
+
+        let mut stream = ChatCompletionStream::default();
+        let mut deltas = Vec::new();
+        stream
+            .push(body.as_bytes(), &mut |delta| deltas.push(delta.to_string()))
+            .unwrap();
+
+        assert!(stream.done);
+        assert_eq!(deltas, ["Hi"]);
+    }
+
+    #[test]
+    fn rejects_malformed_chunks() {
+        let mut stream = ChatCompletionStream::default();
+        assert!(stream.push(b"data: {not json}\n", &mut |_| {}).is_err());
+    }
 }
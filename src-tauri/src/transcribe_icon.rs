@@ -1,45 +1,236 @@
-use anyhow::Result;
-use tauri::{image::Image, tray::TrayIcon};
+use crate::config::IconConfig;
+use anyhow::{Context, Result};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tauri::{async_runtime::spawn, image::Image, tray::TrayIcon};
+use tokio_util::sync::CancellationToken;
 
-#[derive(Debug, Clone, Copy)]
+/// Frames of the spinning animation made from a single icon
+const SPIN_FRAMES: usize = 8;
+
+/// Floor for `frame_ms`, so a tiny value doesn't redraw the tray in a busy loop
+const MIN_FRAME_MS: u64 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Icon {
     Default,
     Recording,
     Transcribing,
     Cleansing,
+    Error,
+    /// No backend is reachable
+    Offline,
 }
 
-pub struct TranscribeIcon(TrayIcon);
+impl Icon {
+    const ALL: [Icon; 6] = [
+        Icon::Default,
+        Icon::Recording,
+        Icon::Transcribing,
+        Icon::Cleansing,
+        Icon::Error,
+        Icon::Offline,
+    ];
+
+    /// Name of the PNG replacing this icon in a theme, without the extension
+    fn file_name(self) -> &'static str {
+        match self {
+            Icon::Default => "default",
+            Icon::Recording => "recording",
+            Icon::Transcribing => "transcribing",
+            Icon::Cleansing => "polishing",
+            Icon::Error => "error",
+            Icon::Offline => "offline",
+        }
+    }
+
+    fn bundled(self) -> &'static [u8] {
+        match self {
+            Icon::Default => include_bytes!("../icons/whistle.png"),
+            Icon::Recording => include_bytes!("../icons/recording-icon.png"),
+            Icon::Transcribing => include_bytes!("../icons/transcribing-icon.png"),
+            Icon::Cleansing => include_bytes!("../icons/polishing-icon.png"),
+            Icon::Error => include_bytes!("../icons/error-icon.png"),
+            Icon::Offline => include_bytes!("../icons/offline-icon.png"),
+        }
+    }
+
+    /// Shown while waiting on a backend
+    fn is_animated(self) -> bool {
+        matches!(self, Icon::Transcribing | Icon::Cleansing)
+    }
+}
+
+pub struct TranscribeIcon {
+    tray_icon: TrayIcon,
+    /// A single frame unless the icon is animated
+    frames: HashMap<Icon, Arc<Vec<Image<'static>>>>,
+    frame_interval: Duration,
+    animation: Mutex<CancellationToken>,
+}
 
 impl TranscribeIcon {
-    pub fn new(tray_icon: TrayIcon) -> Self {
-        Self(tray_icon)
+    pub fn new(tray_icon: TrayIcon, config: &IconConfig) -> Result<Self> {
+        let theme_dir = match &config.theme {
+            Some(theme) => Some(theme_dir(theme)?),
+            None => None,
+        };
+
+        let mut frames = HashMap::new();
+        for icon in Icon::ALL {
+            let animate = config.animate && icon.is_animated();
+            frames.insert(
+                icon,
+                Arc::new(load_frames(icon, theme_dir.as_deref(), animate)?),
+            );
+        }
+
+        let transcribe_icon = Self {
+            tray_icon,
+            frames,
+            frame_interval: Duration::from_millis(config.frame_ms.max(MIN_FRAME_MS)),
+            animation: Mutex::default(),
+        };
+        // The tray starts out with the app icon, which a theme replaces too
+        if theme_dir.is_some() {
+            transcribe_icon.change_icon(Icon::Default);
+        }
+
+        Ok(transcribe_icon)
     }
 
     pub fn change_icon(&self, icon: Icon) {
-        if let Err(e) = self.change_icon_(icon) {
+        let token = CancellationToken::new();
+        let previous =
+            std::mem::replace(&mut *self.animation.lock().unwrap(), token.clone());
+        previous.cancel();
+
+        let frames = Arc::clone(&self.frames[&icon]);
+        if let Err(e) = self.tray_icon.set_icon(Some(frames[0].clone())) {
             log::error!("Unable to change icon: {e}");
-        } else {
-            log::trace!("Successfully changed icon to: {icon:?}");
+            return;
+        }
+        log::trace!("Successfully changed icon to: {icon:?}");
+
+        if frames.len() > 1 {
+            self.animate(frames, token);
         }
     }
 
-    fn change_icon_(&self, icon: Icon) -> Result<()> {
-        let img = match icon {
-            Icon::Default => Image::from_bytes(include_bytes!("../icons/whistle.png"))?,
-            Icon::Recording => {
-                Image::from_bytes(include_bytes!("../icons/recording-icon.png"))?
-            }
-            Icon::Transcribing => {
-                Image::from_bytes(include_bytes!("../icons/transcribing-icon.png"))?
-            }
-            Icon::Cleansing => {
-                Image::from_bytes(include_bytes!("../icons/transcribing-icon.png"))?
+    /// Shows `progress` next to the tray icon, or clears it with `None`
+    pub fn set_progress(&self, progress: Option<&str>) {
+        if let Err(e) = self
+            .tray_icon
+            .set_title(progress)
+            .and_then(|_| self.tray_icon.set_tooltip(progress))
+        {
+            log::error!("Unable to set tray progress: {e}");
+        }
+    }
+
+    /// Cycles through `frames` until the icon changes again
+    fn animate(&self, frames: Arc<Vec<Image<'static>>>, token: CancellationToken) {
+        let tray_icon = self.tray_icon.clone();
+        let interval = self.frame_interval;
+        spawn(async move {
+            for frame in frames.iter().cycle().skip(1) {
+                let slept = token
+                    .run_until_cancelled(tokio::time::sleep(interval))
+                    .await;
+                if slept.is_none() {
+                    break;
+                }
+                if let Err(e) = tray_icon.set_icon(Some(frame.clone())) {
+                    log::error!("Unable to animate icon: {e}");
+                    break;
+                }
             }
-        };
+        });
+    }
+}
+
+fn theme_dir(theme: &str) -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .context("Could not find home directory")?
+        .join(".config/whistle/icons")
+        .join(theme))
+}
 
-        self.0.set_icon(Some(img))?;
+/// The icon from the theme if it has one, otherwise the bundled one. Animated
+/// icons use the theme's numbered frames (`transcribing-1.png`,
+/// `transcribing-2.png`, ...) or spin the single icon.
+fn load_frames(
+    icon: Icon,
+    theme_dir: Option<&Path>,
+    animate: bool,
+) -> Result<Vec<Image<'static>>> {
+    let mut frames = Vec::new();
+
+    if let Some(dir) = theme_dir {
+        if animate {
+            frames = (1..)
+                .map(|n| dir.join(format!("{}-{n}.png", icon.file_name())))
+                .take_while(|path| path.exists())
+                .filter_map(|path| load_themed(&path))
+                .collect();
+        }
+        if frames.is_empty() {
+            let path = dir.join(format!("{}.png", icon.file_name()));
+            frames.extend(load_themed(&path));
+        }
+    }
+
+    if frames.is_empty() {
+        frames.push(Image::from_bytes(icon.bundled())?);
+    }
 
-        Ok(())
+    if animate && frames.len() == 1 {
+        let step = 360.0 / SPIN_FRAMES as f64;
+        frames = (0..SPIN_FRAMES)
+            .map(|n| rotate(&frames[0], step * n as f64))
+            .collect();
     }
+
+    Ok(frames)
+}
+
+/// `None` if the theme doesn't have the file or it isn't a valid PNG
+fn load_themed(path: &Path) -> Option<Image<'static>> {
+    if !path.exists() {
+        return None;
+    }
+    Image::from_path(path)
+        .inspect_err(|e| log::error!("Failed to load icon {}: {e}", path.display()))
+        .ok()
+}
+
+/// `image` turned clockwise by `degrees` around its centre
+fn rotate(image: &Image<'_>, degrees: f64) -> Image<'static> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let rgba = image.rgba();
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+
+    let mut rotated = vec![0; rgba.len()];
+    for y in 0..height {
+        for x in 0..width {
+            // The source pixel that ends up at (x, y)
+            let (dx, dy) = (x as f64 + 0.5 - cx, y as f64 + 0.5 - cy);
+            let sx = (cos * dx + sin * dy + cx).floor();
+            let sy = (cos * dy - sin * dx + cy).floor();
+            if sx < 0.0 || sy < 0.0 || sx >= width as f64 || sy >= height as f64 {
+                continue;
+            }
+
+            let from = (sy as usize * width + sx as usize) * 4;
+            let to = (y * width + x) * 4;
+            rotated[to..to + 4].copy_from_slice(&rgba[from..from + 4]);
+        }
+    }
+
+    Image::new_owned(rotated, width as u32, height as u32)
 }
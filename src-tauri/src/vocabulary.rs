@@ -0,0 +1,199 @@
+/// Builds the hint sent to backends that accept an initial prompt. Whisper
+/// biases its decoding towards the spellings it sees in the prompt.
+pub fn initial_prompt(vocabulary: &[String]) -> Option<String> {
+    let terms = vocabulary
+        .iter()
+        .map(|term| term.trim())
+        .filter(|term| !term.is_empty())
+        .collect::<Vec<_>>();
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(terms.join(", "))
+}
+
+/// Rewrites every case-insensitive, whole-word occurrence of a vocabulary term
+/// with its canonical spelling. Works regardless of whether the backend
+/// honoured the initial prompt.
+pub fn apply_vocabulary(text: &str, vocabulary: &[String]) -> String {
+    vocabulary
+        .iter()
+        .map(|term| term.trim())
+        .filter(|term| !term.is_empty())
+        .fold(text.to_string(), |text, term| replace_term(&text, term))
+}
+
+fn replace_term(text: &str, term: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let term_chars = term.chars().collect::<Vec<_>>();
+
+    let mut corrected = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let end = i + term_chars.len();
+
+        let starts_word = i == 0 || !chars[i - 1].is_alphanumeric();
+        let ends_word = chars.get(end).is_none_or(|c| !c.is_alphanumeric());
+        let matches = end <= chars.len()
+            && chars[i..end]
+                .iter()
+                .zip(&term_chars)
+                .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()));
+
+        if starts_word && ends_word && matches {
+            corrected.push_str(term);
+            i = end;
+            continue;
+        }
+
+        corrected.push(chars[i]);
+        i += 1;
+    }
+
+    corrected
+}
+
+/// Applies the vocabulary to text that arrives a piece at a time, e.g. a
+/// streamed reply that's typed as it comes in. The end is held back until no
+/// term can still match across it.
+pub struct VocabularyStream<'a> {
+    vocabulary: &'a [String],
+    /// Characters in the longest term
+    longest: usize,
+    pending: String,
+}
+
+impl<'a> VocabularyStream<'a> {
+    pub fn new(vocabulary: &'a [String]) -> Self {
+        let longest = vocabulary
+            .iter()
+            .map(|term| term.trim().chars().count())
+            .max()
+            .unwrap_or(0);
+
+        Self {
+            vocabulary,
+            longest,
+            pending: String::new(),
+        }
+    }
+
+    /// Adds `delta` and returns the corrected text that can't change anymore
+    pub fn push(&mut self, delta: &str) -> String {
+        self.pending.push_str(delta);
+        if self.longest == 0 {
+            return std::mem::take(&mut self.pending);
+        }
+
+        let corrected = apply_vocabulary(&self.pending, self.vocabulary);
+        let chars = corrected.chars().collect::<Vec<_>>();
+
+        // Splitting after whitespace keeps words whole, and a term starting
+        // before the split is then followed by a character that already arrived
+        let limit = chars.len().saturating_sub(self.longest);
+        let split = (1..=limit)
+            .rev()
+            .find(|&i| chars[i - 1].is_whitespace())
+            .unwrap_or(0);
+
+        self.pending = chars[split..].iter().collect();
+        chars[..split].iter().collect()
+    }
+
+    /// The rest of the text, once nothing more arrives
+    pub fn finish(self) -> String {
+        apply_vocabulary(&self.pending, self.vocabulary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocabulary(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|term| term.to_string()).collect()
+    }
+
+    fn stream(vocabulary: &[String], deltas: &[&str]) -> Vec<String> {
+        let mut stream = VocabularyStream::new(vocabulary);
+        let mut typed = deltas
+            .iter()
+            .map(|delta| stream.push(delta))
+            .collect::<Vec<_>>();
+        typed.push(stream.finish());
+        typed
+    }
+
+    #[test]
+    fn initial_prompt_joins_non_blank_terms() {
+        let prompt = initial_prompt(&vocabulary(&[" Tauri ", "", "GitHub"]));
+        assert_eq!(prompt.as_deref(), Some("Tauri, GitHub"));
+        assert_eq!(initial_prompt(&vocabulary(&["  "])), None);
+    }
+
+    #[test]
+    fn applies_canonical_spelling_regardless_of_case() {
+        let vocabulary = vocabulary(&["Tauri", "GitHub"]);
+        assert_eq!(
+            apply_vocabulary("pushed the TAURI app to github", &vocabulary),
+            "pushed the Tauri app to GitHub"
+        );
+    }
+
+    #[test]
+    fn only_replaces_whole_words() {
+        let vocabulary = vocabulary(&["rant", "Tauri"]);
+        assert_eq!(
+            apply_vocabulary("a restaurant with tauris", &vocabulary),
+            "a restaurant with tauris"
+        );
+    }
+
+    #[test]
+    fn applies_terms_with_spaces_and_accents() {
+        let vocabulary = vocabulary(&["São Paulo", "Visual Studio Code"]);
+        assert_eq!(
+            apply_vocabulary("from SÃO PAULO, in visual studio code.", &vocabulary),
+            "from São Paulo, in Visual Studio Code."
+        );
+    }
+
+    #[test]
+    fn stream_corrects_terms_split_across_deltas() {
+        let vocabulary = vocabulary(&["Tauri", "Visual Studio Code"]);
+        let typed = stream(
+            &vocabulary,
+            &[
+                "built with tau",
+                "ri in visual stu",
+                "dio code today ",
+                "and more",
+            ],
+        );
+
+        assert_eq!(
+            typed.concat(),
+            "built with Tauri in Visual Studio Code today and more"
+        );
+    }
+
+    #[test]
+    fn stream_holds_back_a_possible_term() {
+        let vocabulary = vocabulary(&["Tauri"]);
+        let mut stream = VocabularyStream::new(&vocabulary);
+
+        assert_eq!(stream.push("hello ta"), "");
+        assert_eq!(stream.push("uri app is great"), "hello Tauri app is ");
+        assert_eq!(stream.finish(), "great");
+    }
+
+    #[test]
+    fn stream_without_vocabulary_passes_text_through() {
+        let typed = stream(&[], &["hello ", "wor", "ld"]);
+
+        assert_eq!(typed, ["hello ", "wor", "ld", ""]);
+    }
+}
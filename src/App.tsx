@@ -1,7 +1,11 @@
 import { useEffect, useState } from "react";
 import MessageDisplay from "./components/MessageDisplay";
 import { getShortcuts } from "./utils/shortcuts";
+import { t } from "./utils/i18n";
 import { ShortcutInput } from "./components/ShortcutInput";
+import { BackendSecretInput } from "./components/BackendSecretInput";
+import { History } from "./components/History";
+import { StatusPanel } from "./components/StatusPanel";
 import "./App.css";
 
 function App() {
@@ -22,12 +26,9 @@ function App() {
 
   return (
     <div className="mt-5 max-w-[800px] mx-auto space-y-3 px-5">
-      <h1 className="text-2xl font-bold text-center">Shortcuts</h1>
+      <h1 className="text-2xl font-bold text-center">{t("app.title")}</h1>
       <div className="mb-5">
-        <p>
-          Register global shortcuts that will work even when the app is in the
-          background.
-        </p>
+        <p>{t("app.intro")}</p>
       </div>
 
       <div
@@ -39,9 +40,9 @@ function App() {
         }}
       >
         <h2 className="text-md font-bold">
-          Current Shortcuts{" "}
+          {t("app.currentShortcuts")}{" "}
           <span className="font-semibold text-sm">
-            (Override shortcuts by setting a new shortcut in the input below)
+            {t("app.currentShortcutsHint")}
           </span>
         </h2>
         <ul className="list-disc list-inside">
@@ -61,6 +62,9 @@ function App() {
           });
         }}
       />
+      <BackendSecretInput />
+      <StatusPanel />
+      <History />
       <MessageDisplay messages={messages} />
     </div>
   );
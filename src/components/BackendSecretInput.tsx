@@ -0,0 +1,47 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { t } from "../utils/i18n";
+
+export function BackendSecretInput() {
+  const [backend, setBackend] = useState("whistle");
+  const [secret, setSecret] = useState("");
+  const [status, setStatus] = useState("");
+
+  return (
+    <div className="pt-5 space-y-3">
+      <h2 className="text-lg font-bold">{t("secret.title")}</h2>
+      <p className="text-sm">{t("secret.hint")}</p>
+      <div className="flex items-center gap-x-3">
+        <input
+          type="text"
+          placeholder={t("secret.backend")}
+          value={backend}
+          onChange={(e) => setBackend(e.target.value)}
+        />
+        <input
+          type="password"
+          placeholder={t("secret.token")}
+          value={secret}
+          onChange={(e) => setSecret(e.target.value)}
+        />
+        <button
+          disabled={backend === ""}
+          className="disabled:opacity-50"
+          onClick={() => {
+            invoke<string>("set_backend_secret", { backend, secret })
+              .then((err) => {
+                setStatus(err === "" ? t("secret.saved") : err);
+                setSecret("");
+              })
+              .catch((err) => {
+                console.error(err);
+              });
+          }}
+        >
+          {t("secret.save")}
+        </button>
+      </div>
+      {status && <p className="text-sm">{status}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,116 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { t } from "../utils/i18n";
+
+type HistoryItem = {
+  id: number;
+  kind: "transcription" | "translation" | "polish";
+  rawText: string;
+  polishedText: string | null;
+  durationMs: number | null;
+  backend: string | null;
+  language: string | null;
+  sinks: string[];
+  hasRecording: boolean;
+  startedAt: string;
+  createdAt: string;
+};
+
+export function History() {
+  const [query, setQuery] = useState("");
+  const [items, setItems] = useState<HistoryItem[]>([]);
+  const [status, setStatus] = useState("");
+  const [backend, setBackend] = useState("");
+  const [language, setLanguage] = useState("");
+
+  const search = (query: string) => {
+    invoke<HistoryItem[]>("search_history", { query })
+      .then((items) => {
+        setItems(items);
+        setStatus("");
+      })
+      .catch((err) => setStatus(err));
+  };
+
+  useEffect(() => {
+    search(query);
+  }, [query]);
+
+  return (
+    <div className="pt-5 space-y-3">
+      <h2 className="text-lg font-bold">{t("history.title")}</h2>
+      <input
+        type="text"
+        placeholder={t("history.search")}
+        value={query}
+        onChange={(e) => setQuery(e.target.value)}
+      />
+      <div className="flex items-center gap-x-3">
+        <span className="text-sm">{t("history.retranscribeWith")}</span>
+        <input
+          type="text"
+          placeholder={t("history.backend")}
+          value={backend}
+          onChange={(e) => setBackend(e.target.value)}
+        />
+        <input
+          type="text"
+          placeholder={t("history.language")}
+          value={language}
+          onChange={(e) => setLanguage(e.target.value)}
+        />
+      </div>
+      {status && <p className="text-sm">{status}</p>}
+      <ul className="space-y-2">
+        {items.map((item) => (
+          <li key={item.id} className="flex items-start gap-x-3">
+            <div className="flex-1">
+              <p>{item.polishedText ?? item.rawText}</p>
+              <p className="text-sm opacity-70">
+                {new Date(item.createdAt).toLocaleString()} ·{" "}
+                {t(`history.kind.${item.kind}`)}
+                {item.backend && ` · ${item.backend}`}
+                {item.language && ` · ${item.language}`}
+                {item.durationMs !== null &&
+                  ` · ${(item.durationMs / 1000).toFixed(1)}s`}
+              </p>
+            </div>
+            {item.hasRecording && (
+              <button
+                onClick={() => {
+                  setStatus(t("history.transcribing"));
+                  invoke<HistoryItem>("retranscribe", {
+                    id: item.id,
+                    backend,
+                    language,
+                  })
+                    .then(() => search(query))
+                    .catch((err) => setStatus(err));
+                }}
+              >
+                {t("history.retranscribe")}
+              </button>
+            )}
+            <button
+              onClick={() => {
+                invoke<string>("delete_history_item", { id: item.id })
+                  .then((err) => {
+                    if (err !== "") {
+                      setStatus(err);
+                      return;
+                    }
+                    search(query);
+                  })
+                  .catch((err) => {
+                    console.error(err);
+                  });
+              }}
+            >
+              {t("history.delete")}
+            </button>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
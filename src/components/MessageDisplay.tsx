@@ -1,3 +1,5 @@
+import { t } from "../utils/i18n";
+
 interface MessageDisplayProps {
   messages: string[];
 }
@@ -16,9 +18,9 @@ export default function MessageDisplay({ messages }: MessageDisplayProps) {
         color: "black",
       }}
     >
-      <h3 className="text-md font-bold">Key presses:</h3>
+      <h3 className="text-md font-bold">{t("keys.title")}</h3>
       {messages.length === 0 ? (
-        <p>No key presses yet</p>
+        <p>{t("keys.empty")}</p>
       ) : (
         <ul className="list-disc list-inside">
           {messages.map((message, index) => (
@@ -1,5 +1,16 @@
 import { useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { t } from "../utils/i18n";
+
+const shortcutNames = [
+  "toggle-recording",
+  "cleanse-clipboard",
+  "cancel",
+  "polish-selection",
+  "repaste-last",
+  "retry-last",
+  "polish-last-paste",
+] as const;
 
 interface ShortcutInputProps {
   onShortcutRegistered: () => void;
@@ -18,7 +29,7 @@ export function ShortcutInput({
   const handleKeyDown = (e: React.KeyboardEvent) => {
     e.preventDefault();
 
-    handleKeyUpDownMsg(`${e.key} down`);
+    handleKeyUpDownMsg(`${e.key} ${t("keys.down")}`);
 
     if (!isSettingShortcut) {
       setIsSettingShortcut(true);
@@ -39,7 +50,7 @@ export function ShortcutInput({
       setSavedKeys(pressedKeys);
     }
 
-    handleKeyUpDownMsg(`${e.key} up`);
+    handleKeyUpDownMsg(`${e.key} ${t("keys.up")}`);
 
     setPressedKeys((prev) => {
       const updated = new Set(prev);
@@ -56,11 +67,11 @@ export function ShortcutInput({
 
   return (
     <div className="pt-5 space-y-3">
-      <h2 className="text-lg font-bold">Set a shortcut</h2>
+      <h2 className="text-lg font-bold">{t("shortcut.title")}</h2>
       <div className=" flex items-center gap-x-3">
         <input
           type="text"
-          placeholder="Press keys to set shortcut"
+          placeholder={t("shortcut.placeholder")}
           value={text}
           onKeyDown={handleKeyDown}
           onKeyUp={handleKeyUp}
@@ -72,9 +83,12 @@ export function ShortcutInput({
             value={selectedShortcut}
             onChange={(e) => setSelectedShortcut(e.target.value)}
           >
-            <option value="">Select a shortcut</option>
-            <option value="toggle-recording">Toggle recording</option>
-            <option value="cleanse-clipboard">Cleanse clipboard</option>
+            <option value="">{t("shortcut.select")}</option>
+            {shortcutNames.map((name) => (
+              <option key={name} value={name}>
+                {t(`shortcut.${name}`)}
+              </option>
+            ))}
           </select>
         </div>
         <div>
@@ -106,7 +120,7 @@ export function ShortcutInput({
                 });
             }}
           >
-            Register
+            {t("shortcut.register")}
           </button>
         </div>
       </div>
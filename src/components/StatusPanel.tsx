@@ -0,0 +1,101 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { t } from "../utils/i18n";
+
+type AppState =
+  | "idle"
+  | "recording"
+  | "paused"
+  | "transcribing"
+  | "polishing"
+  | "pasting"
+  | "error";
+
+type ErrorCode =
+  | "recording_failed"
+  | "transcription_failed"
+  | "translation_failed"
+  | "polish_failed"
+  | "delivery_failed"
+  | "empty_clipboard"
+  | "nothing_selected"
+  | "nothing_to_repeat";
+
+type PipelineEvent =
+  | { kind: "recording_started" }
+  | { kind: "recording_stopped"; durationMs: number | null }
+  | { kind: "transcription_started" }
+  | {
+      kind: "transcription_result";
+      text: string;
+      translation: string | null;
+      backend: string;
+    }
+  | { kind: "polish_started" }
+  | { kind: "polish_result"; original: string; text: string }
+  | { kind: "error"; code: ErrorCode; message: string };
+
+type LogEntry = { at: Date; text: string };
+
+const describe = (event: PipelineEvent): string => {
+  switch (event.kind) {
+    case "recording_started":
+      return t("status.recordingStarted");
+    case "recording_stopped":
+      return event.durationMs === null
+        ? t("status.recordingStopped")
+        : `${t("status.recordingStoppedAfter")} ${(event.durationMs / 1000).toFixed(1)}s`;
+    case "transcription_started":
+      return t("status.transcribing");
+    case "transcription_result":
+      return `${event.backend}: ${event.translation ?? event.text}`;
+    case "polish_started":
+      return t("status.polishing");
+    case "polish_result":
+      return `${t("status.polished")} ${event.text}`;
+    case "error":
+      // The code is localized here, the message is the underlying error
+      return `${t(`status.error.${event.code}`)}: ${event.message}`;
+  }
+};
+
+export function StatusPanel() {
+  const [state, setState] = useState<AppState>("idle");
+  const [log, setLog] = useState<LogEntry[]>([]);
+
+  useEffect(() => {
+    invoke<AppState>("get_app_state").then(setState);
+
+    const unlistenState = listen<AppState>("state-changed", (event) =>
+      setState(event.payload)
+    );
+    const unlistenPipeline = listen<PipelineEvent>("pipeline", (event) =>
+      setLog((prev) =>
+        [{ at: new Date(), text: describe(event.payload) }, ...prev].slice(0, 20)
+      )
+    );
+
+    return () => {
+      unlistenState.then((unlisten) => unlisten());
+      unlistenPipeline.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  return (
+    <div className="pt-5 space-y-3">
+      <h2 className="text-lg font-bold">
+        {t("status.title")}:{" "}
+        <span className="font-semibold">{t(`status.state.${state}`)}</span>
+      </h2>
+      <ul className="space-y-1 text-sm">
+        {log.map((entry, i) => (
+          <li key={i}>
+            <span className="opacity-70">{entry.at.toLocaleTimeString()}</span>{" "}
+            {entry.text}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
@@ -0,0 +1,160 @@
+import { invoke } from "@tauri-apps/api/core";
+
+type Language = "en" | "es";
+
+const english = {
+  "app.title": "Shortcuts",
+  "app.intro":
+    "Register global shortcuts that will work even when the app is in the background.",
+  "app.currentShortcuts": "Current Shortcuts",
+  "app.currentShortcutsHint":
+    "(Override shortcuts by setting a new shortcut in the input below)",
+
+  "keys.title": "Key presses:",
+  "keys.empty": "No key presses yet",
+  "keys.down": "down",
+  "keys.up": "up",
+
+  "shortcut.title": "Set a shortcut",
+  "shortcut.placeholder": "Press keys to set shortcut",
+  "shortcut.select": "Select a shortcut",
+  "shortcut.toggle-recording": "Toggle recording",
+  "shortcut.cleanse-clipboard": "Cleanse clipboard",
+  "shortcut.cancel": "Cancel transcription or polish",
+  "shortcut.polish-selection": "Polish selection",
+  "shortcut.repaste-last": "Paste last transcript again",
+  "shortcut.retry-last": "Retry last recording",
+  "shortcut.polish-last-paste": "Polish last paste",
+  "shortcut.register": "Register",
+
+  "secret.title": "Backend token",
+  "secret.hint":
+    "Stored in your system keychain. Leave the token empty to remove it.",
+  "secret.backend": "Backend name",
+  "secret.token": "Token or API key",
+  "secret.save": "Save",
+  "secret.saved": "Saved",
+
+  "status.title": "Status",
+  "status.state.idle": "idle",
+  "status.state.recording": "recording",
+  "status.state.paused": "paused",
+  "status.state.transcribing": "transcribing",
+  "status.state.polishing": "polishing",
+  "status.state.pasting": "pasting",
+  "status.state.error": "error",
+  "status.recordingStarted": "Recording started",
+  "status.recordingStopped": "Recording stopped",
+  "status.recordingStoppedAfter": "Recording stopped after",
+  "status.transcribing": "Transcribing...",
+  "status.polishing": "Polishing...",
+  "status.polished": "Polished:",
+  "status.error.recording_failed": "Recording failed",
+  "status.error.transcription_failed": "Transcription failed",
+  "status.error.translation_failed": "Translation failed",
+  "status.error.polish_failed": "Polishing failed",
+  "status.error.delivery_failed": "Delivering the text failed",
+  "status.error.empty_clipboard": "The clipboard is empty",
+  "status.error.nothing_selected": "Nothing selected",
+  "status.error.nothing_to_repeat": "Nothing to repeat",
+
+  "history.title": "History",
+  "history.search": "Search transcriptions",
+  "history.retranscribeWith": "Re-transcribe with",
+  "history.backend": "Backend (all by default)",
+  "history.language": "Language, e.g. es",
+  "history.transcribing": "Transcribing...",
+  "history.retranscribe": "Re-transcribe",
+  "history.delete": "Delete",
+  "history.kind.transcription": "transcription",
+  "history.kind.translation": "translation",
+  "history.kind.polish": "polish",
+};
+
+type MessageKey = keyof typeof english;
+
+const spanish: Record<MessageKey, string> = {
+  "app.title": "Atajos",
+  "app.intro":
+    "Registra atajos globales que funcionan aunque la aplicación esté en segundo plano.",
+  "app.currentShortcuts": "Atajos actuales",
+  "app.currentShortcutsHint":
+    "(Cambia un atajo escribiendo uno nuevo en el campo de abajo)",
+
+  "keys.title": "Teclas pulsadas:",
+  "keys.empty": "Todavía no se ha pulsado ninguna tecla",
+  "keys.down": "pulsada",
+  "keys.up": "soltada",
+
+  "shortcut.title": "Asignar un atajo",
+  "shortcut.placeholder": "Pulsa las teclas del atajo",
+  "shortcut.select": "Elige un atajo",
+  "shortcut.toggle-recording": "Iniciar o detener grabación",
+  "shortcut.cleanse-clipboard": "Pulir portapapeles",
+  "shortcut.cancel": "Cancelar transcripción o pulido",
+  "shortcut.polish-selection": "Pulir selección",
+  "shortcut.repaste-last": "Pegar de nuevo la última transcripción",
+  "shortcut.retry-last": "Reintentar última grabación",
+  "shortcut.polish-last-paste": "Pulir último pegado",
+  "shortcut.register": "Registrar",
+
+  "secret.title": "Token del servidor",
+  "secret.hint":
+    "Se guarda en el llavero del sistema. Deja el token vacío para borrarlo.",
+  "secret.backend": "Nombre del servidor",
+  "secret.token": "Token o clave de API",
+  "secret.save": "Guardar",
+  "secret.saved": "Guardado",
+
+  "status.title": "Estado",
+  "status.state.idle": "en espera",
+  "status.state.recording": "grabando",
+  "status.state.paused": "en pausa",
+  "status.state.transcribing": "transcribiendo",
+  "status.state.polishing": "puliendo",
+  "status.state.pasting": "pegando",
+  "status.state.error": "error",
+  "status.recordingStarted": "Grabación iniciada",
+  "status.recordingStopped": "Grabación detenida",
+  "status.recordingStoppedAfter": "Grabación detenida tras",
+  "status.transcribing": "Transcribiendo...",
+  "status.polishing": "Puliendo...",
+  "status.polished": "Pulido:",
+  "status.error.recording_failed": "No se pudo grabar",
+  "status.error.transcription_failed": "No se pudo transcribir",
+  "status.error.translation_failed": "No se pudo traducir",
+  "status.error.polish_failed": "No se pudo pulir",
+  "status.error.delivery_failed": "No se pudo entregar el texto",
+  "status.error.empty_clipboard": "El portapapeles está vacío",
+  "status.error.nothing_selected": "Nada seleccionado",
+  "status.error.nothing_to_repeat": "Nada que repetir",
+
+  "history.title": "Historial",
+  "history.search": "Buscar transcripciones",
+  "history.retranscribeWith": "Volver a transcribir con",
+  "history.backend": "Servidor (todos por defecto)",
+  "history.language": "Idioma, p. ej. en",
+  "history.transcribing": "Transcribiendo...",
+  "history.retranscribe": "Volver a transcribir",
+  "history.delete": "Borrar",
+  "history.kind.transcription": "transcripción",
+  "history.kind.translation": "traducción",
+  "history.kind.polish": "pulido",
+};
+
+const catalogs: Record<Language, Record<MessageKey, string>> = {
+  en: english,
+  es: spanish,
+};
+
+let language: Language = "en";
+
+// Follows the language the backend picked for the tray and notifications, so
+// call this before rendering
+export async function loadLanguage() {
+  language = await invoke<Language>("get_language");
+}
+
+export function t(key: MessageKey): string {
+  return catalogs[language][key];
+}